@@ -1,113 +1,133 @@
-use p3_field::PrimeCharacteristicRing;
-use p3_matrix::Matrix;
 use core::borrow::Borrow;
-use crate::movement_air::{MovementAir, MovementRow};
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+
+use crate::movement_air::{MovementRow, DELTA_TIME_SCALE, NUM_AGENTS};
 
 type Val = p3_baby_bear::BabyBear;
 
-// Self-contained constraint checking - replicates our MovementAir constraints
-// This doesn't depend on any Plonky3 modifications
-pub fn check_movement_constraints(air: &MovementAir, trace_matrix: &p3_matrix::dense::RowMajorMatrix<Val>) -> Result<(), String> {
+/// A single constraint violation found while scanning a trace matrix, identified by
+/// which row/agent slot it occurred in so a caller can decide what to do with it -
+/// e.g. `CheatPolicy` exempting a sanctioned player from a hard failure.
+#[derive(Debug, Clone)]
+pub struct ConstraintViolation {
+    pub row: usize,
+    pub agent: usize,
+    pub message: String,
+}
+
+/// Off-chain mirror of `MovementAir`'s Constraints 1-3 (boolean inputs, velocity-from-
+/// inputs, and position continuity) - the checks whose violation indicates an actual
+/// cheat attempt, as opposed to the range-check/bounds/public-value constraints, which
+/// only guard against a malicious *prover* rather than a malicious *player*. Gives
+/// cheap, readable diagnostics before (or instead of) running the full STARK prover.
+///
+/// Every violation is collected into the returned `Err` rather than `panic!`king on
+/// the first one, so the caller decides whether a violation is fatal for a given
+/// entity (see `CheatPolicy`) instead of this function terminating the process.
+pub fn check_movement_constraints(trace_matrix: &RowMajorMatrix<Val>) -> Result<(), Vec<ConstraintViolation>> {
     let height = trace_matrix.height();
-    println!("🔍 CONSTRAINT_DEBUG: Checking {} rows for violations", height);
-    
-    let mut violations_found = 0;
-    
+    let mut violations = Vec::new();
+
+    let velocity_offset = Val::from_u64(1000);
+    let movement_speed = Val::from_u64(200);
+    let dt_scale = Val::from_u64(DELTA_TIME_SCALE);
+    let position_scale = Val::from_u64(1000);
+
     for row_index in 0..height {
-        let row_index_next = (row_index + 1) % height;
-        
-        // Get current and next rows
-        let local_row = trace_matrix.row_slice(row_index).ok_or("Failed to get local row")?;
-        let next_row = trace_matrix.row_slice(row_index_next).ok_or("Failed to get next row")?;
-        
+        let local_row = trace_matrix.row_slice(row_index).expect("row_index < height");
         let local: &MovementRow<Val> = (&*local_row).borrow();
-        let next: &MovementRow<Val> = (&*next_row).borrow();
-        
-        // Constraint 1: Boolean inputs (each input flag is 0 or 1)
-        if local.input_left != Val::ZERO && local.input_left != Val::ONE {
-            return Err(format!("Row {}: input_left {} is not boolean", row_index, local.input_left));
-        }
-        if local.input_right != Val::ZERO && local.input_right != Val::ONE {
-            return Err(format!("Row {}: input_right {} is not boolean", row_index, local.input_right));
-        }
-        if local.input_up != Val::ZERO && local.input_up != Val::ONE {
-            return Err(format!("Row {}: input_up {} is not boolean", row_index, local.input_up));
-        }
-        if local.input_down != Val::ZERO && local.input_down != Val::ONE {
-            return Err(format!("Row {}: input_down {} is not boolean", row_index, local.input_down));
-        }
 
-        // Constraint 2: Velocity must match inputs exactly
-        let velocity_offset = Val::from_u64(1000);
-        let movement_speed = Val::from_u64(200);
-        
-        let expected_vel_x = (local.input_right - local.input_left) * movement_speed + velocity_offset;
-        let expected_vel_y = (local.input_up - local.input_down) * movement_speed + velocity_offset;
-        
-        // Log every row that has movement or input
-        if local.velocity_x != velocity_offset || local.velocity_y != velocity_offset || 
-           local.input_left != Val::ZERO || local.input_right != Val::ZERO || 
-           local.input_up != Val::ZERO || local.input_down != Val::ZERO {
-            println!("🔍 CONSTRAINT Row {}: pos=({},{}) vel=({},{}) inputs=({},{},{},{}) expected_vel=({},{})", 
-                     row_index, local.position_x, local.position_y, 
-                     local.velocity_x, local.velocity_y,
-                     local.input_left, local.input_right, local.input_up, local.input_down,
-                     expected_vel_x, expected_vel_y);
-        }
-        
-        if local.velocity_x != expected_vel_x {
-            violations_found += 1;
-            println!("❌ CONSTRAINT VIOLATION Row {}: velocity_x {} != expected {} (speed hacking detected)", 
-                     row_index, local.velocity_x, expected_vel_x);
-            panic!("🚨 CHEATING DETECTED! 🚨 Row {}: velocity_x {} != expected {} (speed hacking detected) - GAME TERMINATED", 
-                   row_index, local.velocity_x, expected_vel_x);
-        }
-        
-        if local.velocity_y != expected_vel_y {
-            violations_found += 1;
-            println!("❌ CONSTRAINT VIOLATION Row {}: velocity_y {} != expected {} (speed hacking detected)", 
-                     row_index, local.velocity_y, expected_vel_y);
-            panic!("🚨 CHEATING DETECTED! 🚨 Row {}: velocity_y {} != expected {} (speed hacking detected) - GAME TERMINATED", 
-                   row_index, local.velocity_y, expected_vel_y);
-        }
-        
-        // Constraint 3: Position continuity - prevents teleportation
-        if row_index != height - 1 { // Not the last row (no wraparound)
-            let actual_next_vel_x = next.velocity_x - velocity_offset;
-            let actual_next_vel_y = next.velocity_y - velocity_offset;
-            let physics_factor = Val::from_u64(15);
-            
-            let expected_next_x = local.position_x + actual_next_vel_x * physics_factor;
-            let expected_next_y = local.position_y + actual_next_vel_y * physics_factor;
-            
-            // Log position transitions
-            if actual_next_vel_x != Val::ZERO || actual_next_vel_y != Val::ZERO {
-                println!("🎯 CONSTRAINT Transition {}->{}: pos ({},{}) + vel({},{}) * 15 = expected ({},{}) vs actual ({},{})", 
-                         row_index, row_index + 1,
-                         local.position_x, local.position_y,
-                         actual_next_vel_x, actual_next_vel_y,
-                         expected_next_x, expected_next_y,
-                         next.position_x, next.position_y);
+        for agent in 0..NUM_AGENTS {
+            let local_agent = &local.agents[agent];
+
+            // Constraint 1: boolean inputs (and the player/NPC selector itself).
+            for (name, value) in [
+                ("input_left", local_agent.input_left),
+                ("input_right", local_agent.input_right),
+                ("input_up", local_agent.input_up),
+                ("input_down", local_agent.input_down),
+                ("is_player", local_agent.is_player),
+            ] {
+                if value != Val::ZERO && value != Val::ONE {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: format!("{name} is not boolean"),
+                    });
+                }
             }
-            
-            if next.position_x != expected_next_x {
-                violations_found += 1;
-                println!("❌ CONSTRAINT VIOLATION Row {}: position_x {} != expected {} (teleportation detected)", 
-                         row_index, next.position_x, expected_next_x);
-                panic!("🚨 CHEATING DETECTED! 🚨 Row {}: position_x {} != expected {} (teleportation detected) - GAME TERMINATED", 
-                       row_index, next.position_x, expected_next_x);
+
+            // Constraint 2 (players only): velocity must match inputs exactly.
+            if local_agent.is_player == Val::ONE {
+                let expected_vel_x = (local_agent.input_right - local_agent.input_left) * movement_speed + velocity_offset;
+                let expected_vel_y = (local_agent.input_up - local_agent.input_down) * movement_speed + velocity_offset;
+
+                if local_agent.velocity_x != expected_vel_x {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: "velocity_x does not match inputs (speed hacking)".to_string(),
+                    });
+                }
+                if local_agent.velocity_y != expected_vel_y {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: "velocity_y does not match inputs (speed hacking)".to_string(),
+                    });
+                }
             }
-            
-            if next.position_y != expected_next_y {
-                violations_found += 1;
-                println!("❌ CONSTRAINT VIOLATION Row {}: position_y {} != expected {} (teleportation detected)", 
-                         row_index, next.position_y, expected_next_y);
-                panic!("🚨 CHEATING DETECTED! 🚨 Row {}: position_y {} != expected {} (teleportation detected) - GAME TERMINATED", 
-                       row_index, next.position_y, expected_next_y);
+
+            // Constraint 3: position continuity - prevents teleportation. Mirrors
+            // `MovementAir`'s quotient/remainder transition check, using the next
+            // row's own quotient/remainder/delta_time columns.
+            if row_index + 1 < height {
+                let next_row = trace_matrix.row_slice(row_index + 1).expect("row_index + 1 < height");
+                let next: &MovementRow<Val> = (&*next_row).borrow();
+                let next_agent = &next.agents[agent];
+
+                let actual_next_vel_x = next_agent.velocity_x - velocity_offset;
+                let actual_next_vel_y = next_agent.velocity_y - velocity_offset;
+
+                let vel_dt_x = actual_next_vel_x * next_agent.delta_time;
+                let vel_dt_y = actual_next_vel_y * next_agent.delta_time;
+
+                let expected_vel_dt_x = next_agent.quotient_x * dt_scale + next_agent.remainder_x;
+                let expected_vel_dt_y = next_agent.quotient_y * dt_scale + next_agent.remainder_y;
+
+                if vel_dt_x != expected_vel_dt_x || vel_dt_y != expected_vel_dt_y {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: "velocity * delta_time does not match the supplied quotient/remainder".to_string(),
+                    });
+                }
+
+                let expected_next_x = local_agent.position_x + next_agent.quotient_x * position_scale;
+                let expected_next_y = local_agent.position_y + next_agent.quotient_y * position_scale;
+
+                if next_agent.position_x != expected_next_x {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: "position_x does not follow from velocity (teleportation)".to_string(),
+                    });
+                }
+                if next_agent.position_y != expected_next_y {
+                    violations.push(ConstraintViolation {
+                        row: row_index,
+                        agent,
+                        message: "position_y does not follow from velocity (teleportation)".to_string(),
+                    });
+                }
             }
         }
     }
-    
-    println!("✅ CONSTRAINT_DEBUG: All {} rows passed, {} violations found", height, violations_found);
-    Ok(())
-}
\ No newline at end of file
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::winit::WinitSettings;
-use crate::{Player, ProofGenerator, Velocity};
+use crate::movement_trace::MovementTraceCollector;
+use crate::{HudLog, Player, ProofGenerator, Velocity};
 
 #[derive(Component)]
 struct FpsText;
@@ -12,6 +13,12 @@ struct ProofStatsText;
 #[derive(Component)]
 struct VelocityText;
 
+/// Scrolling log of recent proof outcomes and constraint violations - the persistent
+/// counterpart to the one-shot red `CheatPopup`, so the anti-cheat is observable
+/// frame-to-frame instead of only in the terminal.
+#[derive(Component)]
+struct HudLogText;
+
 pub struct FpsDisplayPlugin;
 
 impl Plugin for FpsDisplayPlugin {
@@ -23,9 +30,10 @@ impl Plugin for FpsDisplayPlugin {
             })
             .add_systems(Startup, setup_fps_display)
             .add_systems(Update, (
-                update_fps_display, 
+                update_fps_display,
                 update_proof_stats_display,
                 update_velocity_display,
+                update_hud_log_display,
             ));
     }
 }
@@ -85,6 +93,17 @@ fn setup_fps_display(mut commands: Commands) {
                         },
                         VelocityText,
                     ));
+
+                    // Scrolling log of recent proof outcomes / constraint violations
+                    stats_parent.spawn((
+                        Text::new(""),
+                        TextColor(Color::srgb(0.7, 1.0, 0.7)),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        HudLogText,
+                    ));
                 });
         });
 }
@@ -116,22 +135,33 @@ fn update_fps_display(
 fn update_proof_stats_display(
     mut text_query: Query<&mut Text, With<ProofStatsText>>,
     mut color_query: Query<&mut TextColor, With<ProofStatsText>>,
-    proof_query: Query<&ProofGenerator, With<Player>>,
+    proof_query: Query<(&ProofGenerator, &MovementTraceCollector), With<Player>>,
 ) {
     if let (Ok(mut text), Ok(mut text_color)) = (text_query.get_single_mut(), color_query.get_single_mut()) {
-        if let Ok(proof_gen) = proof_query.get_single() {
+        // Local multiplayer has several player entities now; the HUD only has room for
+        // one compact summary line, so show the first player's stats (consistent with
+        // this display's original single-player scope).
+        if let Some((proof_gen, trace_collector)) = proof_query.iter().next() {
             let active_count = proof_gen.active_tasks.len();
             let generated_count = proof_gen.stats.total_proofs_generated;
+            let failed_count = proof_gen.stats.failed_verifications;
             let avg_time = proof_gen.stats.avg_generation_time();
-            
+            let trace_len = trace_collector.current_trace.as_ref().map(|trace| trace.steps.len()).unwrap_or(0);
+
             let avg_verification_time = proof_gen.stats.avg_verification_time();
+            let queue_depth = proof_gen.stats.queue_depth;
+            let rejected_count = proof_gen.stats.rejected_count;
+            let gen_summary = proof_gen.stats.generation_summary();
             **text = format!(
-                "Proofs: Active: {}, Generated: {}, Avg Gen: {:.1}ms, Avg Verify: {:.1}ms", 
-                active_count, generated_count, avg_time / 1_000_000.0, avg_verification_time / 1_000_000.0
+                "Proofs: Active: {}, Generated: {}, Failed: {}, Trace: {} steps, Avg Gen: {:.1}ms, Avg Verify: {:.1}ms, Queued: {}, Rejected: {}\nGen tail - p50: {:.1}ms, p95: {:.1}ms, p99: {:.1}ms",
+                active_count, generated_count, failed_count, trace_len, avg_time, avg_verification_time, queue_depth, rejected_count,
+                gen_summary.median, gen_summary.p95, gen_summary.p99
             );
-            
+
             // Color-code based on activity
-            text_color.0 = if active_count > 0 {
+            text_color.0 = if failed_count > 0 {
+                Color::srgb(1.0, 0.2, 0.2) // Red once a verification has failed
+            } else if active_count > 0 {
                 Color::srgb(1.0, 0.8, 0.0) // Orange when actively generating
             } else {
                 Color::srgb(0.8, 0.8, 1.0) // Light blue when idle
@@ -140,6 +170,21 @@ fn update_proof_stats_display(
     }
 }
 
+/// Shows the last several proof outcomes / constraint violations, newest at the
+/// bottom, so a caught cheat's specific constraint and row are visible on-screen
+/// instead of only in the terminal log.
+fn update_hud_log_display(
+    mut text_query: Query<&mut Text, With<HudLogText>>,
+    hud_log: Res<HudLog>,
+) {
+    if !hud_log.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        **text = hud_log.entries.iter().cloned().collect::<Vec<_>>().join("\n");
+    }
+}
+
 
 fn update_velocity_display(
     mut text_query: Query<&mut Text, With<VelocityText>>,
@@ -147,7 +192,8 @@ fn update_velocity_display(
     player_query: Query<&Velocity, With<Player>>,
 ) {
     if let (Ok(mut text), Ok(mut text_color)) = (text_query.get_single_mut(), color_query.get_single_mut()) {
-        if let Ok(velocity) = player_query.get_single() {
+        // Same first-player scoping as `update_proof_stats_display` above.
+        if let Some(velocity) = player_query.iter().next() {
             let speed = ((velocity.x * velocity.x + velocity.y * velocity.y) as f32).sqrt();
             let normal_speed = 200.0 * 1.414; // sqrt(200^2 + 200^2) for diagonal movement
             
@@ -0,0 +1,130 @@
+// Structured metrics export for proof-generation runs, mirroring how a test runner
+// offers `json`/`junit` output modes alongside its default human-readable one.
+// `stats_logging_system`'s periodic `info!` lines are fine for a developer watching
+// a terminal, but can't be consumed by CI or a dashboard - this gives a batch replay
+// of a session a report those systems can actually ingest.
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use bevy::prelude::Resource;
+
+/// Which structured format proof records are written in. `Pretty` is the status
+/// quo - `stats_logging_system`'s periodic `info!` summaries - and writes nothing
+/// to disk; `Jsonl`/`JUnit` are for CI/dashboard consumption.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MetricsFormat {
+    #[default]
+    Pretty,
+    Jsonl,
+    JUnit,
+}
+
+/// Where `Jsonl`/`JUnit` records are written. Unused (and the file is never
+/// touched) while `ProofSystemSettings::metrics_format` is `Pretty`.
+#[derive(Resource)]
+pub struct MetricsExportConfig {
+    pub path: PathBuf,
+}
+
+impl Default for MetricsExportConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("proof_metrics"),
+        }
+    }
+}
+
+/// Accumulates every record seen so far so `JUnit` mode can (re)write one complete
+/// `<testsuite>` on each flush - JUnit reports aren't streamable the way JSON Lines
+/// are, since the root element's `tests`/`failures` counts need the full set.
+#[derive(Resource, Default)]
+pub struct MetricsExportState {
+    testcases: Vec<ProofMetricRecord>,
+}
+
+/// One completed proof's outcome, independent of which format it's eventually
+/// rendered in.
+pub struct ProofMetricRecord {
+    pub player_id: usize,
+    pub generation_time_ms: f64,
+    pub verification_time_ms: f64,
+    pub proof_size: usize,
+    pub success: bool,
+    pub cheat_detected: bool,
+    pub failure_message: Option<String>,
+}
+
+/// Records one proof's outcome in `format`, writing to `config.path` for
+/// `Jsonl`/`JUnit`. `Pretty` is a no-op here - that format is handled entirely by
+/// `stats_logging_system`'s existing `info!` logging.
+pub fn record_proof_metric(
+    config: &MetricsExportConfig,
+    state: &mut MetricsExportState,
+    format: MetricsFormat,
+    record: ProofMetricRecord,
+) -> std::io::Result<()> {
+    match format {
+        MetricsFormat::Pretty => Ok(()),
+        MetricsFormat::Jsonl => append_jsonl(&config.path.with_extension("jsonl"), &record),
+        MetricsFormat::JUnit => {
+            state.testcases.push(record);
+            write_junit(&config.path.with_extension("xml"), &state.testcases)
+        }
+    }
+}
+
+fn append_jsonl(path: &std::path::Path, record: &ProofMetricRecord) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let line = format!(
+        "{{\"player_id\":{},\"generation_time_ms\":{:.3},\"verification_time_ms\":{:.3},\"proof_size\":{},\"success\":{},\"cheat_detected\":{}}}\n",
+        record.player_id,
+        record.generation_time_ms,
+        record.verification_time_ms,
+        record.proof_size,
+        record.success,
+        record.cheat_detected,
+    );
+    file.write_all(line.as_bytes())
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_junit(path: &std::path::Path, testcases: &[ProofMetricRecord]) -> std::io::Result<()> {
+    let failures = testcases.iter().filter(|t| !t.success).count();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuite name=\"proof-generation\" tests=\"{}\" failures=\"{}\">\n",
+        testcases.len(),
+        failures
+    ));
+
+    for (index, testcase) in testcases.iter().enumerate() {
+        let time_secs = testcase.generation_time_ms / 1000.0;
+        out.push_str(&format!(
+            "  <testcase name=\"player{}_proof{}\" time=\"{:.6}\">\n",
+            testcase.player_id, index, time_secs
+        ));
+        if !testcase.success {
+            let message = testcase.failure_message.as_deref().unwrap_or("proof verification failed");
+            out.push_str(&format!(
+                "    <failure message=\"{}\">cheat_detected={}</failure>\n",
+                xml_escape(message),
+                testcase.cheat_detected
+            ));
+        }
+        out.push_str("  </testcase>\n");
+    }
+
+    out.push_str("</testsuite>\n");
+
+    let mut file = File::create(path)?;
+    file.write_all(out.as_bytes())
+}
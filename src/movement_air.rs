@@ -1,18 +1,163 @@
 use core::borrow::Borrow;
-use p3_air::{Air, AirBuilder, BaseAir};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
 use p3_field::{PrimeField64, PrimeCharacteristicRing};
 use p3_matrix::{Matrix, dense::RowMajorMatrix};
 use crate::movement_trace::MovementTrace;
 
-// Number of columns in our AIR
-pub const NUM_MOVEMENT_COLS: usize = 8;
+/// Number of agent slots proven per row. A slot is either a keyboard-controlled
+/// player (`is_player = 1`) or a flocking NPC (`is_player = 0`) driven by the
+/// separation rule in `eval`; unused slots are padded as stationary NPCs at the
+/// origin. This lets one proof attest to an entire simulation frame - every player
+/// and every NPC - rather than a single entity in isolation.
+pub const NUM_AGENTS: usize = 4;
 
-pub struct MovementAir;
+/// Public values bound to the first/last row of a proof, per agent: that agent's
+/// starting and ending encoded position. Consecutive traces chain by having trace
+/// N+1's public "initial position" equal trace N's public "final position" for
+/// each agent slot - closing the one-frame gap between proofs a teleporting prover
+/// could otherwise hide in.
+pub const NUM_PUBLIC_VALUES: usize = NUM_AGENTS * 4;
+
+// The exclusive upper bounds the trace encoding in `generate_movement_trace_matrix`
+// guarantees for each encoded value. A prover who adds a multiple of the field's
+// modulus to a cell would otherwise still satisfy every `assert_eq` below, so we
+// range-check each value against these bounds via bit decomposition.
+pub const POSITION_ENCODING_BOUND: u64 = 100_000_000;
+pub const VELOCITY_ENCODING_BOUND: u64 = 2_000;
+
+/// Number of bits needed to range-check a value known to lie in `[0, bound)`.
+/// Keeping this as a `const fn` (rather than a hand-picked literal) is what keeps
+/// `NUM_MOVEMENT_COLS` in sync if the encoding bounds above ever change.
+pub const fn bits_for_bound(bound: u64) -> usize {
+    let mut bits = 0usize;
+    let mut cap: u64 = 1;
+    while cap < bound {
+        cap <<= 1;
+        bits += 1;
+    }
+    bits
+}
+
+pub const POSITION_BITS: usize = bits_for_bound(POSITION_ENCODING_BOUND);
+pub const VELOCITY_BITS: usize = bits_for_bound(VELOCITY_ENCODING_BOUND);
+
+// `delta_time` is encoded as whole milliseconds-of-a-second (e.g. 0.016s -> 16) so
+// it stays an exact integer in the field. `velocity * delta_time_millis` is not an
+// exact multiple of this scale, so the AIR uses the non-deterministic
+// quotient/remainder trick: the prover supplies the (integer-pixel) quotient and
+// the millisecond remainder of that division, and the remainder is range-checked
+// below to keep the decomposition unique.
+pub const DELTA_TIME_SCALE: u64 = 1000;
+pub const REMAINDER_BITS: usize = bits_for_bound(DELTA_TIME_SCALE);
+
+// The flocking separation rule pulls an NPC this fraction of the summed offset to
+// its flockmates towards them each step (`SEPARATION_STRENGTH_NUM / SEPARATION_SCALE`).
+// Like `DELTA_TIME_SCALE` above, that division isn't exact, so it uses the same
+// non-deterministic quotient/remainder trick.
+pub const SEPARATION_SCALE: u64 = 100;
+pub const SEPARATION_STRENGTH_NUM: u64 = 5;
+pub const SEPARATION_REMAINDER_BITS: usize = bits_for_bound(SEPARATION_SCALE);
+
+/// Columns describing a single agent (player or NPC) within a row. `NUM_AGENTS` of
+/// these make up one `MovementRow`.
+#[repr(C)]
+pub struct AgentColumns<F> {
+    pub position_x: F,
+    pub position_y: F,
+    pub velocity_x: F,
+    pub velocity_y: F,
+    pub input_left: F,
+    pub input_right: F,
+    pub input_up: F,
+    pub input_down: F,
+    // 1 if this slot is a keyboard-controlled player (Constraint 2 applies), 0 if
+    // it's an NPC driven by the separation rule (Constraint 4 applies) instead.
+    pub is_player: F,
+    // Little-endian bit decompositions used to range-check the values above.
+    pub position_x_bits: [F; POSITION_BITS],
+    pub position_y_bits: [F; POSITION_BITS],
+    pub velocity_x_bits: [F; VELOCITY_BITS],
+    pub velocity_y_bits: [F; VELOCITY_BITS],
+    // The delta_time this row was sampled with (encoded as whole milliseconds), and
+    // the non-deterministic quotient/remainder of `velocity * delta_time` used to
+    // prove the position transition that arrived at this row.
+    pub delta_time: F,
+    pub quotient_x: F,
+    pub quotient_y: F,
+    pub remainder_x: F,
+    pub remainder_y: F,
+    pub remainder_x_bits: [F; REMAINDER_BITS],
+    pub remainder_y_bits: [F; REMAINDER_BITS],
+    // Bit decompositions of `position - min` and `max - position` for each axis,
+    // proving the position lies within the arena's game_bounds.
+    pub position_x_above_min_bits: [F; POSITION_BITS],
+    pub position_x_below_max_bits: [F; POSITION_BITS],
+    pub position_y_above_min_bits: [F; POSITION_BITS],
+    pub position_y_below_max_bits: [F; POSITION_BITS],
+    // Non-deterministic quotient/remainder of the summed neighbor-offset (scaled by
+    // SEPARATION_STRENGTH_NUM) that arrived at this row's velocity from the previous
+    // row's, used by Constraint 4. Filled in for every slot, but only constrained
+    // for NPC slots (Constraint 4 is gated off for player-controlled slots).
+    pub separation_quotient_x: F,
+    pub separation_quotient_y: F,
+    pub separation_remainder_x: F,
+    pub separation_remainder_y: F,
+    pub separation_remainder_x_bits: [F; SEPARATION_REMAINDER_BITS],
+    pub separation_remainder_y_bits: [F; SEPARATION_REMAINDER_BITS],
+}
+
+/// Width, in field columns, of a single `AgentColumns<F>`.
+pub const AGENT_COLS: usize = 9
+    + 2 * POSITION_BITS
+    + 2 * VELOCITY_BITS
+    + 5
+    + 2 * REMAINDER_BITS
+    + 4 * POSITION_BITS
+    + 4
+    + 2 * SEPARATION_REMAINDER_BITS;
+
+// Number of columns in our AIR: `NUM_AGENTS` agent blocks, each `AGENT_COLS` wide.
+pub const NUM_MOVEMENT_COLS: usize = NUM_AGENTS * AGENT_COLS;
+
+/// Encodes a game-world coordinate the same way `generate_movement_trace_matrix`
+/// encodes `position_x`/`position_y`, so arena bounds can be compared against them
+/// directly in the field.
+pub fn encode_position(value: f32) -> u64 {
+    let scaled = (value * 1000.0) as i64;
+    ((scaled + 50_000_000) as u64) % POSITION_ENCODING_BOUND
+}
+
+pub struct MovementAir {
+    // Encoded arena bounds (same scheme as position_x/position_y) the proof enforces.
+    min_x: u64,
+    max_x: u64,
+    min_y: u64,
+    max_y: u64,
+}
 
 impl MovementAir {
-    pub fn new(_movement_speed: f32, _game_bounds: (f32, f32, f32, f32), _delta_time: f32) -> Self {
-        Self
+    pub fn new(_movement_speed: f32, game_bounds: (f32, f32, f32, f32), _delta_time: f32) -> Self {
+        let (min_x, max_x, min_y, max_y) = game_bounds;
+        Self {
+            min_x: encode_position(min_x),
+            max_x: encode_position(max_x),
+            min_y: encode_position(min_y),
+            max_y: encode_position(max_y),
+        }
+    }
+}
+
+/// Asserts that every element of `bits` is boolean and that they recompose (as a
+/// little-endian binary number) to `value`. Since `bits.len()` bits can only ever
+/// represent `[0, 2^bits.len())`, this also proves `value < 2^bits.len()`.
+fn eval_bit_decomposition<AB: AirBuilder>(builder: &mut AB, value: AB::Expr, bits: &[AB::Var]) {
+    let mut sum = AB::Expr::ZERO;
+    for (i, bit) in bits.iter().enumerate() {
+        builder.assert_bool(bit.clone());
+        let weight = AB::F::from_u64(1u64 << i);
+        sum = sum + bit.clone().into() * AB::Expr::from(weight);
     }
+    builder.assert_eq(value, sum);
 }
 
 impl<F> BaseAir<F> for MovementAir {
@@ -21,76 +166,243 @@ impl<F> BaseAir<F> for MovementAir {
     }
 }
 
-impl<AB: AirBuilder> Air<AB> for MovementAir {
+impl<AB: AirBuilderWithPublicValues> Air<AB> for MovementAir {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        
+
         // Get current and next rows (for state transitions)
         let (local, next) = (
             main.row_slice(0).expect("Matrix is empty?"),
             main.row_slice(1).expect("Matrix only has 1 row?"),
         );
-        
+
         let local: &MovementRow<AB::Var> = (*local).borrow();
         let next: &MovementRow<AB::Var> = (*next).borrow();
 
-        // Constraint 1: Boolean inputs (each input flag is 0 or 1)
-        builder.assert_bool(local.input_left.clone());
-        builder.assert_bool(local.input_right.clone());
-        builder.assert_bool(local.input_up.clone());
-        builder.assert_bool(local.input_down.clone());
-
-        // Constraint 2: Velocity must match inputs exactly
-        // Account for the +1000 offset used in trace generation for negative velocities
         let velocity_offset = AB::F::from_u64(1000); // Offset to handle negative velocities
         let movement_speed = AB::F::from_u64(200); // Must match actual game speed
-        
-        // Expected velocity calculation: input * speed + offset
-        let expected_vel_x = (local.input_right.clone() - local.input_left.clone()) * AB::Expr::from(movement_speed) + AB::Expr::from(velocity_offset);
-        let expected_vel_y = (local.input_up.clone() - local.input_down.clone()) * AB::Expr::from(movement_speed) + AB::Expr::from(velocity_offset);
-        
-        // Velocity constraint - this should catch speed hacking
-        builder.assert_eq(local.velocity_x.clone(), expected_vel_x);
-        builder.assert_eq(local.velocity_y.clone(), expected_vel_y);
-        
-        // Constraint 3: Position continuity - prevents teleportation
-        // Use the NEXT frame's velocity to validate the position change (original approach)
-        let mut when_transition = builder.when_transition();
-        
-        // Use the NEXT frame's velocity to validate the position change that occurred
-        let actual_next_vel_x = next.velocity_x.clone() - AB::Expr::from(velocity_offset);
-        let actual_next_vel_y = next.velocity_y.clone() - AB::Expr::from(velocity_offset);
-        
-        // Physics factor: velocity * 15 = position_change (from our integer physics)
-        let physics_factor = AB::F::from_u64(15);
-        
-        // Expected position based on the velocity that caused this movement
-        let expected_next_x = local.position_x.clone() + actual_next_vel_x * AB::Expr::from(physics_factor);
-        let expected_next_y = local.position_y.clone() + actual_next_vel_y * AB::Expr::from(physics_factor);
-        
-        
-        // These must match exactly - any deviation (including teleportation) will fail
-        when_transition.assert_eq(next.position_x.clone(), expected_next_x);
-        when_transition.assert_eq(next.position_y.clone(), expected_next_y);
-
-        // Constraint 4: First trace after reset must start at origin (0,0) with velocity (0,0)  
-        // This is enforced by checking the first step in generate_movement_trace_matrix
-        // The constraint is already enforced during trace generation, not here to avoid complexity
-        
+        let dt_scale = AB::F::from_u64(DELTA_TIME_SCALE);
+        let position_scale = AB::F::from_u64(1000);
+        let min_x = AB::F::from_u64(self.min_x);
+        let max_x = AB::F::from_u64(self.max_x);
+        let min_y = AB::F::from_u64(self.min_y);
+        let max_y = AB::F::from_u64(self.max_y);
+        let separation_scale = AB::F::from_u64(SEPARATION_SCALE);
+        let separation_strength = AB::F::from_u64(SEPARATION_STRENGTH_NUM);
+
+        let public_values = builder.public_values().to_vec();
+
+        for agent in 0..NUM_AGENTS {
+            let local_agent = &local.agents[agent];
+            let next_agent = &next.agents[agent];
+
+            // Constraint 1: Boolean inputs (each input flag is 0 or 1), and the
+            // player/NPC selector itself is boolean.
+            builder.assert_bool(local_agent.input_left.clone());
+            builder.assert_bool(local_agent.input_right.clone());
+            builder.assert_bool(local_agent.input_up.clone());
+            builder.assert_bool(local_agent.input_down.clone());
+            builder.assert_bool(local_agent.is_player.clone());
+
+            // Constraint 2 (players only): velocity must match inputs exactly.
+            // Account for the +1000 offset used in trace generation for negative velocities.
+            let expected_vel_x = (local_agent.input_right.clone() - local_agent.input_left.clone())
+                * AB::Expr::from(movement_speed)
+                + AB::Expr::from(velocity_offset);
+            let expected_vel_y = (local_agent.input_up.clone() - local_agent.input_down.clone())
+                * AB::Expr::from(movement_speed)
+                + AB::Expr::from(velocity_offset);
+
+            let mut when_player = builder.when(local_agent.is_player.clone());
+            when_player.assert_eq(local_agent.velocity_x.clone(), expected_vel_x);
+            when_player.assert_eq(local_agent.velocity_y.clone(), expected_vel_y);
+
+            // Constraint 3: Position continuity - prevents teleportation. Uses the
+            // NEXT frame's velocity (and the delta_time it was sampled with) to
+            // validate the position change that occurred, regardless of whether
+            // that velocity came from a keyboard (Constraint 2) or flocking
+            // (Constraint 4).
+            let mut when_transition = builder.when_transition();
+
+            let actual_next_vel_x = next_agent.velocity_x.clone() - AB::Expr::from(velocity_offset);
+            let actual_next_vel_y = next_agent.velocity_y.clone() - AB::Expr::from(velocity_offset);
+
+            let vel_dt_x = actual_next_vel_x * next_agent.delta_time.clone().into();
+            let vel_dt_y = actual_next_vel_y * next_agent.delta_time.clone().into();
+
+            // velocity * delta_time isn't an exact multiple of DELTA_TIME_SCALE, so the
+            // prover supplies the quotient (whole pixels moved) and remainder (leftover
+            // milli-pixels, dropped by our integer Position) of that division.
+            when_transition.assert_eq(
+                vel_dt_x,
+                next_agent.quotient_x.clone().into() * AB::Expr::from(dt_scale) + next_agent.remainder_x.clone().into(),
+            );
+            when_transition.assert_eq(
+                vel_dt_y,
+                next_agent.quotient_y.clone().into() * AB::Expr::from(dt_scale) + next_agent.remainder_y.clone().into(),
+            );
+
+            // The encoded position carries the same x1000 scale the quotient needs to be
+            // lifted into before it can move `position_x`/`position_y`.
+            let expected_next_x =
+                local_agent.position_x.clone() + next_agent.quotient_x.clone().into() * AB::Expr::from(position_scale);
+            let expected_next_y =
+                local_agent.position_y.clone() + next_agent.quotient_y.clone().into() * AB::Expr::from(position_scale);
+
+            // These must match exactly - any deviation (including teleportation) will fail
+            when_transition.assert_eq(next_agent.position_x.clone(), expected_next_x);
+            when_transition.assert_eq(next_agent.position_y.clone(), expected_next_y);
+
+            // The remainder must lie in [0, DELTA_TIME_SCALE) or the quotient/remainder
+            // pair above would not be unique, letting a prover fudge the position delta.
+            eval_bit_decomposition(builder, local_agent.remainder_x.clone().into(), &local_agent.remainder_x_bits);
+            eval_bit_decomposition(builder, local_agent.remainder_y.clone().into(), &local_agent.remainder_y_bits);
+
+            // Constraint 4 (NPCs only): separation steering. Each NPC's velocity
+            // changes by a bounded fraction of the summed offset to every other
+            // agent in the row - proven with the same quotient/remainder trick as
+            // Constraint 3, so the verifier can confirm the NPC obeyed the
+            // published flocking parameters rather than being puppeteered.
+            //
+            // Scoped down from a true radius-gated flock: every other agent slot
+            // in the row counts as a flockmate (no distance cutoff), which keeps
+            // this constraint set bounded instead of growing with a per-pair
+            // "in range" selector. A scene only needs to populate nearby NPCs into
+            // the same row's slots for this to behave like real separation.
+            let mut neighbor_offset_x = AB::Expr::ZERO;
+            let mut neighbor_offset_y = AB::Expr::ZERO;
+            for other in 0..NUM_AGENTS {
+                if other == agent {
+                    continue;
+                }
+                let other_agent = &local.agents[other];
+                neighbor_offset_x =
+                    neighbor_offset_x + (other_agent.position_x.clone().into() - local_agent.position_x.clone().into());
+                neighbor_offset_y =
+                    neighbor_offset_y + (other_agent.position_y.clone().into() - local_agent.position_y.clone().into());
+            }
+            let scaled_offset_x = neighbor_offset_x * AB::Expr::from(separation_strength);
+            let scaled_offset_y = neighbor_offset_y * AB::Expr::from(separation_strength);
+
+            let mut when_npc_transition = when_transition.when(AB::Expr::ONE - next_agent.is_player.clone().into());
+            when_npc_transition.assert_eq(
+                scaled_offset_x,
+                next_agent.separation_quotient_x.clone().into() * AB::Expr::from(separation_scale)
+                    + next_agent.separation_remainder_x.clone().into(),
+            );
+            when_npc_transition.assert_eq(
+                scaled_offset_y,
+                next_agent.separation_quotient_y.clone().into() * AB::Expr::from(separation_scale)
+                    + next_agent.separation_remainder_y.clone().into(),
+            );
+            when_npc_transition.assert_eq(
+                next_agent.velocity_x.clone(),
+                local_agent.velocity_x.clone() + next_agent.separation_quotient_x.clone().into(),
+            );
+            when_npc_transition.assert_eq(
+                next_agent.velocity_y.clone(),
+                local_agent.velocity_y.clone() + next_agent.separation_quotient_y.clone().into(),
+            );
+
+            eval_bit_decomposition(
+                builder,
+                local_agent.separation_remainder_x.clone().into(),
+                &local_agent.separation_remainder_x_bits,
+            );
+            eval_bit_decomposition(
+                builder,
+                local_agent.separation_remainder_y.clone().into(),
+                &local_agent.separation_remainder_y_bits,
+            );
+
+            // Constraint 5: Range-check every encoded value against the bound its encoding
+            // assumes, so a prover can't wrap the field to alias a different value.
+            eval_bit_decomposition(builder, local_agent.position_x.clone().into(), &local_agent.position_x_bits);
+            eval_bit_decomposition(builder, local_agent.position_y.clone().into(), &local_agent.position_y_bits);
+            eval_bit_decomposition(builder, local_agent.velocity_x.clone().into(), &local_agent.velocity_x_bits);
+            eval_bit_decomposition(builder, local_agent.velocity_y.clone().into(), &local_agent.velocity_y_bits);
+
+            // Constraint 6: Enforce the arena's game_bounds as a first-class, prover-enforced
+            // invariant. `position - min` and `max - position` are each proven representable
+            // in POSITION_BITS bits, which forces both to be non-negative - i.e. min <= position <= max.
+            let x_above_min = local_agent.position_x.clone().into() - AB::Expr::from(min_x);
+            let x_below_max = AB::Expr::from(max_x) - local_agent.position_x.clone().into();
+            let y_above_min = local_agent.position_y.clone().into() - AB::Expr::from(min_y);
+            let y_below_max = AB::Expr::from(max_y) - local_agent.position_y.clone().into();
+
+            eval_bit_decomposition(builder, x_above_min, &local_agent.position_x_above_min_bits);
+            eval_bit_decomposition(builder, x_below_max, &local_agent.position_x_below_max_bits);
+            eval_bit_decomposition(builder, y_above_min, &local_agent.position_y_above_min_bits);
+            eval_bit_decomposition(builder, y_below_max, &local_agent.position_y_below_max_bits);
+
+            // Constraint 7: Bind this proof's first and last row positions to its public
+            // values. The verifier checks those public values against the previous proof's
+            // declared final position (or the origin, after a reset), so a prover can't
+            // teleport in the gap between one proof's last row and the next proof's first.
+            let initial_position_x: AB::Expr = public_values[agent * 4].into();
+            let initial_position_y: AB::Expr = public_values[agent * 4 + 1].into();
+            let final_position_x: AB::Expr = public_values[agent * 4 + 2].into();
+            let final_position_y: AB::Expr = public_values[agent * 4 + 3].into();
+
+            let mut when_first_row = builder.when_first_row();
+            when_first_row.assert_eq(local_agent.position_x.clone(), initial_position_x);
+            when_first_row.assert_eq(local_agent.position_y.clone(), initial_position_y);
+
+            let mut when_last_row = builder.when_last_row();
+            when_last_row.assert_eq(local_agent.position_x.clone(), final_position_x);
+            when_last_row.assert_eq(local_agent.position_y.clone(), final_position_y);
+        }
     }
 }
 
-// Structure representing a single row in our trace
+// Structure representing a single row in our trace: one block of columns per agent.
 #[repr(C)]
 pub struct MovementRow<F> {
-    pub position_x: F,
-    pub position_y: F,
-    pub velocity_x: F,
-    pub velocity_y: F,
-    pub input_left: F,
-    pub input_right: F,
-    pub input_up: F,
-    pub input_down: F,
+    pub agents: [AgentColumns<F>; NUM_AGENTS],
+}
+
+/// Splits `value` into `N` little-endian bits. `value` must be `< 2^N`, which holds
+/// for every encoded value `generate_movement_trace_matrix` produces.
+fn encode_bits<F: PrimeField64, const N: usize>(value: u64) -> [F; N] {
+    let mut bits = [F::ZERO; N];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = if (value >> i) & 1 == 1 { F::ONE } else { F::ZERO };
+    }
+    bits
+}
+
+/// Encodes a (possibly negative) integer as a field element, unlike the other
+/// columns in this file this one isn't range-checked since it's only ever combined
+/// with an already range-checked position.
+fn encode_signed<F: PrimeField64>(value: i64) -> F {
+    if value >= 0 {
+        F::from_u64(value as u64)
+    } else {
+        -F::from_u64((-value) as u64)
+    }
+}
+
+/// Computes the public values a proof of `traces` must be generated (and verified)
+/// with - see Constraint 7 in `eval`. Call this with the same `traces` slice passed
+/// to `generate_movement_trace_matrix` so the bound values match the matrix's
+/// actual first/last row for every agent slot.
+pub fn trace_public_values<F: PrimeField64>(traces: &[MovementTrace]) -> Vec<F> {
+    let mut values = Vec::with_capacity(NUM_PUBLIC_VALUES);
+    for agent in 0..NUM_AGENTS {
+        let (first_position, last_position) = match traces.get(agent) {
+            Some(trace) => (
+                trace.steps.first().map(|step| step.position).unwrap_or_default(),
+                trace.steps.last().map(|step| step.position).unwrap_or_default(),
+            ),
+            None => Default::default(),
+        };
+
+        values.push(F::from_u64(encode_position(first_position.x)));
+        values.push(F::from_u64(encode_position(first_position.y)));
+        values.push(F::from_u64(encode_position(last_position.x)));
+        values.push(F::from_u64(encode_position(last_position.y)));
+    }
+    values
 }
 
 impl<F> Borrow<MovementRow<F>> for [F] {
@@ -104,162 +416,293 @@ impl<F> Borrow<MovementRow<F>> for [F] {
     }
 }
 
-// Function to generate trace matrix from MovementTrace
+/// Per-agent, per-row state resolved from a `MovementTrace` before quotient/remainder
+/// columns can be computed, since the separation rule for row `i` needs every
+/// agent's encoded position at row `i - 1` (see `generate_movement_trace_matrix`).
+struct ResolvedAgentRow {
+    encoded_pos_x: u64,
+    encoded_pos_y: u64,
+    encoded_vel_x: u64,
+    encoded_vel_y: u64,
+    is_player: bool,
+    input_left: bool,
+    input_right: bool,
+    input_up: bool,
+    input_down: bool,
+    dt_millis: i64,
+}
+
+/// Resolves agent `agent_index`'s state at every row `0..target_height` from
+/// `trace`, replicating the single-agent padding behavior (hold the last step's
+/// position, zero velocity) that `trace.steps.len() < target_height` used to apply
+/// directly in-line.
+fn resolve_agent_rows(trace: Option<&MovementTrace>, target_height: usize) -> Vec<ResolvedAgentRow> {
+    let Some(trace) = trace else {
+        // An unused agent slot: a stationary NPC parked at the encoded origin.
+        return (0..target_height)
+            .map(|_| ResolvedAgentRow {
+                encoded_pos_x: encode_position(0.0),
+                encoded_pos_y: encode_position(0.0),
+                encoded_vel_x: 1000,
+                encoded_vel_y: 1000,
+                is_player: false,
+                input_left: false,
+                input_right: false,
+                input_up: false,
+                input_down: false,
+                dt_millis: 0,
+            })
+            .collect();
+    };
+
+    let mut rows = Vec::with_capacity(target_height);
+    for step in trace.steps.iter().take(target_height) {
+        let pos_x_scaled = (step.position.x * 1000.0) as i64;
+        let pos_y_scaled = (step.position.y * 1000.0) as i64;
+        let vel_x_scaled = step.velocity.x as i64;
+        let vel_y_scaled = step.velocity.y as i64;
+
+        rows.push(ResolvedAgentRow {
+            encoded_pos_x: ((pos_x_scaled + 50_000_000) as u64) % POSITION_ENCODING_BOUND,
+            encoded_pos_y: ((pos_y_scaled + 50_000_000) as u64) % POSITION_ENCODING_BOUND,
+            encoded_vel_x: ((vel_x_scaled + 1000) as u64) % VELOCITY_ENCODING_BOUND,
+            encoded_vel_y: ((vel_y_scaled + 1000) as u64) % VELOCITY_ENCODING_BOUND,
+            is_player: trace.is_player,
+            input_left: step.inputs.left,
+            input_right: step.inputs.right,
+            input_up: step.inputs.up,
+            input_down: step.inputs.down,
+            dt_millis: (step.delta_time * DELTA_TIME_SCALE as f32).round() as i64,
+        });
+    }
+
+    if let Some(last_step) = trace.steps.last() {
+        let last_pos_x_scaled = (last_step.position.x * 1000.0) as i64;
+        let last_pos_y_scaled = (last_step.position.y * 1000.0) as i64;
+        let padded_pos_x = ((last_pos_x_scaled + 50_000_000) as u64) % POSITION_ENCODING_BOUND;
+        let padded_pos_y = ((last_pos_y_scaled + 50_000_000) as u64) % POSITION_ENCODING_BOUND;
+
+        while rows.len() < target_height {
+            rows.push(ResolvedAgentRow {
+                encoded_pos_x: padded_pos_x,
+                encoded_pos_y: padded_pos_y,
+                // No movement in padding rows: velocity = 0 + offset = 1000
+                encoded_vel_x: 1000,
+                encoded_vel_y: 1000,
+                is_player: trace.is_player,
+                input_left: false,
+                input_right: false,
+                input_up: false,
+                input_down: false,
+                dt_millis: 0,
+            });
+        }
+    }
+
+    rows
+}
+
+// Function to generate a combined trace matrix proving every agent slot in
+// `traces` for one simulation frame at a time. `traces` may contain fewer than
+// `NUM_AGENTS` entries (e.g. a single player); remaining slots are padded as
+// stationary NPCs.
 pub fn generate_movement_trace_matrix<F: PrimeField64>(
-    trace: &MovementTrace,
+    traces: &[MovementTrace],
     target_height: usize,
+    game_bounds: (f32, f32, f32, f32),
 ) -> RowMajorMatrix<F> {
     assert!(target_height.is_power_of_two());
-    assert!(trace.steps.len() <= target_height, "Trace too long for target height");
+    assert!(traces.len() <= NUM_AGENTS, "More traces than agent slots");
 
-    let mut matrix = RowMajorMatrix::new(
-        F::zero_vec(target_height * NUM_MOVEMENT_COLS),
-        NUM_MOVEMENT_COLS,
-    );
+    for trace in traces {
+        assert!(trace.steps.len() <= target_height, "Trace too long for target height");
+
+        // Chained traces must start exactly where the previous trace's proof left off
+        // (or, after a reset, at the origin) - otherwise a cheater could teleport in the
+        // one-frame gap between proofs.
+        if let Some((expected_position, expected_velocity)) = trace.expected_initial_state {
+            if let Some(first_step) = trace.steps.first() {
+                if first_step.position != expected_position || first_step.velocity != expected_velocity {
+                    panic!(
+                        "Trace does not continue from the expected chained state: expected pos={:?} vel={:?}, got pos={:?} vel={:?}",
+                        expected_position, expected_velocity, first_step.position, first_step.velocity
+                    );
+                }
+            }
+        }
+    }
+
+    let (bounds_min_x, bounds_max_x, bounds_min_y, bounds_max_y) = game_bounds;
+    let min_x = encode_position(bounds_min_x);
+    let max_x = encode_position(bounds_max_x);
+    let min_y = encode_position(bounds_min_y);
+    let max_y = encode_position(bounds_max_y);
+
+    // Resolve every agent slot's per-row state up front: the separation rule below
+    // needs every *other* agent's position at the previous row, which isn't
+    // available yet while filling in a single agent's own rows.
+    let agent_rows: Vec<Vec<ResolvedAgentRow>> = (0..NUM_AGENTS)
+        .map(|agent| resolve_agent_rows(traces.get(agent), target_height))
+        .collect();
+
+    let mut matrix = RowMajorMatrix::new(F::zero_vec(target_height * NUM_MOVEMENT_COLS), NUM_MOVEMENT_COLS);
 
     let (prefix, rows, suffix) = unsafe { matrix.values.align_to_mut::<MovementRow<F>>() };
     assert!(prefix.is_empty(), "Alignment should match");
     assert!(suffix.is_empty(), "Alignment should match");
     assert_eq!(rows.len(), target_height);
 
-    // Fill rows with trace data
-    for (i, step) in trace.steps.iter().enumerate() {
-        if i >= target_height {
-            break;
-        }
-        
-        // CRITICAL: Enforce that first trace after reset starts at origin
-        if trace.is_first_trace_after_reset && i == 0 {
-            if step.position.x != 0.0 || step.position.y != 0.0 || step.velocity.x != 0.0 || step.velocity.y != 0.0 {
-                panic!("First trace after reset must start at origin with zero velocity");
-            }
-        }
+    for i in 0..target_height {
+        let columns: Vec<AgentColumns<F>> = (0..NUM_AGENTS)
+            .map(|agent| {
+                let this_row = &agent_rows[agent][i];
+
+                let x_above_min = (this_row.encoded_pos_x as i64 - min_x as i64) as u64;
+                let x_below_max = (max_x as i64 - this_row.encoded_pos_x as i64) as u64;
+                let y_above_min = (this_row.encoded_pos_y as i64 - min_y as i64) as u64;
+                let y_below_max = (max_y as i64 - this_row.encoded_pos_y as i64) as u64;
+
+                // This row's own delta_time/quotient/remainder describe the transition
+                // that arrived at this row. Row 0 has no prior row, so it carries zeros.
+                let (quotient_x, remainder_x, quotient_y, remainder_y) = if i == 0 {
+                    (0i64, 0u64, 0i64, 0u64)
+                } else {
+                    let vel_x_scaled = this_row.encoded_vel_x as i64 - 1000;
+                    let vel_y_scaled = this_row.encoded_vel_y as i64 - 1000;
+                    let vel_dt_x = vel_x_scaled * this_row.dt_millis;
+                    let vel_dt_y = vel_y_scaled * this_row.dt_millis;
+                    (
+                        vel_dt_x.div_euclid(DELTA_TIME_SCALE as i64),
+                        vel_dt_x.rem_euclid(DELTA_TIME_SCALE as i64) as u64,
+                        vel_dt_y.div_euclid(DELTA_TIME_SCALE as i64),
+                        vel_dt_y.rem_euclid(DELTA_TIME_SCALE as i64) as u64,
+                    )
+                };
+
+                // This row's separation quotient/remainder describe how this row's
+                // velocity arrived from the previous row's, using the *previous* row's
+                // neighbor positions (mirroring how delta_time/quotient above describe
+                // the transition from the previous row). Row 0 has no prior row, so it
+                // carries zeros.
+                let (separation_quotient_x, separation_remainder_x, separation_quotient_y, separation_remainder_y) =
+                    if i == 0 {
+                        (0i64, 0u64, 0i64, 0u64)
+                    } else {
+                        let mut neighbor_offset_x = 0i64;
+                        let mut neighbor_offset_y = 0i64;
+                        for other in 0..NUM_AGENTS {
+                            if other == agent {
+                                continue;
+                            }
+                            let prev_other = &agent_rows[other][i - 1];
+                            let prev_this = &agent_rows[agent][i - 1];
+                            neighbor_offset_x += prev_other.encoded_pos_x as i64 - prev_this.encoded_pos_x as i64;
+                            neighbor_offset_y += prev_other.encoded_pos_y as i64 - prev_this.encoded_pos_y as i64;
+                        }
+                        let scaled_offset_x = neighbor_offset_x * SEPARATION_STRENGTH_NUM as i64;
+                        let scaled_offset_y = neighbor_offset_y * SEPARATION_STRENGTH_NUM as i64;
+                        (
+                            scaled_offset_x.div_euclid(SEPARATION_SCALE as i64),
+                            scaled_offset_x.rem_euclid(SEPARATION_SCALE as i64) as u64,
+                            scaled_offset_y.div_euclid(SEPARATION_SCALE as i64),
+                            scaled_offset_y.rem_euclid(SEPARATION_SCALE as i64) as u64,
+                        )
+                    };
+
+                AgentColumns {
+                    position_x: F::from_u64(this_row.encoded_pos_x),
+                    position_y: F::from_u64(this_row.encoded_pos_y),
+                    velocity_x: F::from_u64(this_row.encoded_vel_x),
+                    velocity_y: F::from_u64(this_row.encoded_vel_y),
+                    input_left: if this_row.input_left { F::ONE } else { F::ZERO },
+                    input_right: if this_row.input_right { F::ONE } else { F::ZERO },
+                    input_up: if this_row.input_up { F::ONE } else { F::ZERO },
+                    input_down: if this_row.input_down { F::ONE } else { F::ZERO },
+                    is_player: if this_row.is_player { F::ONE } else { F::ZERO },
+                    position_x_bits: encode_bits(this_row.encoded_pos_x),
+                    position_y_bits: encode_bits(this_row.encoded_pos_y),
+                    velocity_x_bits: encode_bits(this_row.encoded_vel_x),
+                    velocity_y_bits: encode_bits(this_row.encoded_vel_y),
+                    delta_time: F::from_u64(this_row.dt_millis.max(0) as u64),
+                    quotient_x: encode_signed(quotient_x),
+                    quotient_y: encode_signed(quotient_y),
+                    remainder_x: F::from_u64(remainder_x),
+                    remainder_y: F::from_u64(remainder_y),
+                    remainder_x_bits: encode_bits(remainder_x),
+                    remainder_y_bits: encode_bits(remainder_y),
+                    position_x_above_min_bits: encode_bits(x_above_min),
+                    position_x_below_max_bits: encode_bits(x_below_max),
+                    position_y_above_min_bits: encode_bits(y_above_min),
+                    position_y_below_max_bits: encode_bits(y_below_max),
+                    separation_quotient_x: encode_signed(separation_quotient_x),
+                    separation_quotient_y: encode_signed(separation_quotient_y),
+                    separation_remainder_x: F::from_u64(separation_remainder_x),
+                    separation_remainder_y: F::from_u64(separation_remainder_y),
+                    separation_remainder_x_bits: encode_bits(separation_remainder_x),
+                    separation_remainder_y_bits: encode_bits(separation_remainder_y),
+                }
+            })
+            .collect();
 
-        // Convert to fixed-point representation that matches AIR expectations
-        // Scale positions by 1000 for precision, handle negatives properly
-        let pos_x_scaled = (step.position.x * 1000.0) as i64;
-        let pos_y_scaled = (step.position.y * 1000.0) as i64;
-        let vel_x_scaled = step.velocity.x as i64; // Keep velocities as integers
-        let vel_y_scaled = step.velocity.y as i64;
-        
-        // Expand encoding range to support much larger game boundaries
-        // BabyBear field can hold ~2 billion, so we can safely use 100M range (±50k pixels)
-        let encoded_pos_x = ((pos_x_scaled + 50000000) as u64) % 100000000;
-        let encoded_pos_y = ((pos_y_scaled + 50000000) as u64) % 100000000;
-        let encoded_vel_x = ((vel_x_scaled + 1000) as u64) % 2000;
-        let encoded_vel_y = ((vel_y_scaled + 1000) as u64) % 2000;
-        
-        // Enhanced debug logging - show ALL rows and check for problematic values  
-        let is_interesting = i < 10 || (encoded_vel_x != 1000 || encoded_vel_y != 1000) || 
-                           pos_x_scaled.abs() > 10000000 || pos_y_scaled.abs() > 10000000 ||
-                           encoded_pos_x > 90000000 || encoded_pos_y > 90000000;
-                           
-        // Check for large position jumps that indicate teleportation
-        let has_large_jump = if i > 0 {
-            let prev_step = &trace.steps[i-1];
-            let curr_step = step;
-            let distance = ((curr_step.position.x - prev_step.position.x).powi(2) + 
-                           (curr_step.position.y - prev_step.position.y).powi(2)).sqrt();
-            distance > 50.0
-        } else { false };
-        
-        if is_interesting || has_large_jump {
-            let dt = step.delta_time;
-            let _expected_pos_change_x = step.velocity.x * dt * 1000.0;
-            let _expected_pos_change_y = step.velocity.y * dt * 1000.0;
-            
-            // Calculate what the constraint expects from inputs
-            let expected_vel_x_from_inputs = (if step.inputs.right { 1.0 } else { 0.0 } - if step.inputs.left { 1.0 } else { 0.0 }) * 200.0;
-            let expected_vel_y_from_inputs = (if step.inputs.up { 1.0 } else { 0.0 } - if step.inputs.down { 1.0 } else { 0.0 }) * 200.0;
-            let constraint_expected_vel_x = expected_vel_x_from_inputs + 1000.0; // With offset
-            let constraint_expected_vel_y = expected_vel_y_from_inputs + 1000.0; // With offset
-            
-            // If this is a transition row, show what previous row was
-            let transition_info = if i > 0 && i < trace.steps.len() - 1 {
-                let prev_step = &trace.steps[i-1];
-                format!(" [TRANSITION from vel={:.1} to vel={:.1}]", prev_step.velocity.x, step.velocity.x)
-            } else { String::new() };
-            
-            // Show constraint violation details
-            let vel_x_violation = if (encoded_vel_x as f32 - constraint_expected_vel_x as f32).abs() > 0.1 { "❌" } else { "✅" };
-            let vel_y_violation = if (encoded_vel_y as f32 - constraint_expected_vel_y as f32).abs() > 0.1 { "❌" } else { "✅" };
-            
-            // Field overflow warning - now supports much larger positions
-            let overflow_warning = if pos_x_scaled.abs() > 10000000 || pos_y_scaled.abs() > 10000000 {
-                " ⚠️ LARGE_POSITION"
-            } else if encoded_pos_x > 90000000 || encoded_pos_y > 90000000 {
-                " ⚠️ ENCODING_OVERFLOW"
-            } else { "" };
-            
-            let teleport_warning = if has_large_jump {
-                " 🚨 TELEPORT_IN_TRACE"
-            } else { "" };
-            
-        }
-        
         rows[i] = MovementRow {
-            position_x: F::from_u64(encoded_pos_x),
-            position_y: F::from_u64(encoded_pos_y),
-            velocity_x: F::from_u64(encoded_vel_x),
-            velocity_y: F::from_u64(encoded_vel_y),
-            input_left: if step.inputs.left { F::ONE } else { F::ZERO },
-            input_right: if step.inputs.right { F::ONE } else { F::ZERO },
-            input_up: if step.inputs.up { F::ONE } else { F::ZERO },
-            input_down: if step.inputs.down { F::ONE } else { F::ZERO },
+            agents: columns.try_into().unwrap_or_else(|_| panic!("agent column count must equal NUM_AGENTS")),
         };
     }
 
-    // Pad remaining rows with the last step (or zeros if empty)
-    if !trace.steps.is_empty() {
-        let last_step = &trace.steps[trace.steps.len() - 1];
-        let last_pos_x_scaled = (last_step.position.x * 1000.0) as i64;
-        let last_pos_y_scaled = (last_step.position.y * 1000.0) as i64;
-        
-        for i in trace.steps.len()..target_height {
-            rows[i] = MovementRow {
-                // Keep last position with same encoding
-                position_x: F::from_u64(((last_pos_x_scaled + 50000000) as u64) % 100000000),
-                position_y: F::from_u64(((last_pos_y_scaled + 50000000) as u64) % 100000000),
-                // No movement in padding rows: velocity = 0 + offset = 1000
-                velocity_x: F::from_u64(1000), 
-                velocity_y: F::from_u64(1000),
-                input_left: F::ZERO,
-                input_right: F::ZERO,
-                input_up: F::ZERO,
-                input_down: F::ZERO,
-            };
-        }
-    }
-
     matrix
 }
 
 // Helper function to generate a matrix that will intentionally fail constraint validation
 // This is used when we detect cheating during trace generation
 fn generate_cheat_detected_matrix<F: PrimeField64>(target_height: usize) -> RowMajorMatrix<F> {
-    
-    let mut matrix = RowMajorMatrix::new(
-        F::zero_vec(target_height * NUM_MOVEMENT_COLS),
-        NUM_MOVEMENT_COLS,
-    );
+    let mut matrix = RowMajorMatrix::new(F::zero_vec(target_height * NUM_MOVEMENT_COLS), NUM_MOVEMENT_COLS);
 
     let (prefix, rows, suffix) = unsafe { matrix.values.align_to_mut::<MovementRow<F>>() };
     assert!(prefix.is_empty(), "Alignment should match");
     assert!(suffix.is_empty(), "Alignment should match");
     assert_eq!(rows.len(), target_height);
 
-    // Generate a matrix that will definitely fail constraint validation
-    // Set invalid values that violate the velocity constraint
+    // Generate a matrix that will definitely fail constraint validation: every
+    // agent slot gets an invalid velocity that doesn't match its (zeroed) inputs.
     for i in 0..target_height {
+        let columns: Vec<AgentColumns<F>> = (0..NUM_AGENTS)
+            .map(|_| AgentColumns {
+                position_x: F::from_u64(50_000_000), // Encoded (0,0)
+                position_y: F::from_u64(50_000_000), // Encoded (0,0)
+                velocity_x: F::from_u64(9999),        // Invalid velocity that doesn't match inputs
+                velocity_y: F::from_u64(9999),        // Invalid velocity that doesn't match inputs
+                input_left: F::ZERO,
+                input_right: F::ZERO,
+                input_up: F::ZERO,
+                input_down: F::ZERO,
+                is_player: F::ONE,
+                position_x_bits: encode_bits(50_000_000),
+                position_y_bits: encode_bits(50_000_000),
+                velocity_x_bits: encode_bits(9999),
+                velocity_y_bits: encode_bits(9999),
+                delta_time: F::ZERO,
+                quotient_x: F::ZERO,
+                quotient_y: F::ZERO,
+                remainder_x: F::ZERO,
+                remainder_y: F::ZERO,
+                remainder_x_bits: encode_bits(0),
+                remainder_y_bits: encode_bits(0),
+                // (0,0) is trivially within any sane game_bounds.
+                position_x_above_min_bits: encode_bits(50_000_000),
+                position_x_below_max_bits: encode_bits(50_000_000),
+                position_y_above_min_bits: encode_bits(50_000_000),
+                position_y_below_max_bits: encode_bits(50_000_000),
+                separation_quotient_x: F::ZERO,
+                separation_quotient_y: F::ZERO,
+                separation_remainder_x: F::ZERO,
+                separation_remainder_y: F::ZERO,
+                separation_remainder_x_bits: encode_bits(0),
+                separation_remainder_y_bits: encode_bits(0),
+            })
+            .collect();
+
         rows[i] = MovementRow {
-            position_x: F::from_u64(50000000), // Encoded (0,0)
-            position_y: F::from_u64(50000000), // Encoded (0,0)
-            velocity_x: F::from_u64(9999),     // Invalid velocity that doesn't match inputs
-            velocity_y: F::from_u64(9999),     // Invalid velocity that doesn't match inputs
-            input_left: F::ZERO,
-            input_right: F::ZERO,
-            input_up: F::ZERO,
-            input_down: F::ZERO,
+            agents: columns.try_into().unwrap_or_else(|_| panic!("agent column count must equal NUM_AGENTS")),
         };
     }
 
@@ -276,4 +719,4 @@ pub fn next_power_of_2(n: usize) -> usize {
         power <<= 1;
     }
     power
-}
\ No newline at end of file
+}
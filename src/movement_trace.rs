@@ -9,6 +9,10 @@ pub struct MovementStep {
     pub inputs: InputFlags,
     pub timestamp: f64,
     pub delta_time: f32, // Actual frame delta time
+    /// The monotonic simulation frame this step was collected on. Used by
+    /// `MovementTraceCollector::rollback_to_frame` to discard rows from frames a
+    /// rollback-netcode resimulation has since mispredicted.
+    pub frame: i32,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -25,14 +29,26 @@ pub struct MovementTrace {
     pub steps: Vec<MovementStep>,
     pub start_time: f64,
     pub duration: f64,
+    /// The (position, velocity) this trace's first step must match - either the
+    /// previous trace's final state (chaining traces together) or the origin after
+    /// a reset. `None` for the very first trace of a session, which has nothing to
+    /// chain against.
+    pub expected_initial_state: Option<(Vec2, Vec2)>,
+    /// Which `MovementAir` agent kind this trace represents - a keyboard-controlled
+    /// player (Constraint 2 applies) or a flocking NPC (Constraint 4 applies
+    /// instead). Read by `generate_movement_trace_matrix` so a populated agent slot
+    /// is marked correctly instead of every real trace being assumed a player.
+    pub is_player: bool,
 }
 
 impl MovementTrace {
-    pub fn new(start_time: f64) -> Self {
+    pub fn new(start_time: f64, expected_initial_state: Option<(Vec2, Vec2)>, is_player: bool) -> Self {
         Self {
             steps: Vec::new(),
             start_time,
             duration: 0.0,
+            expected_initial_state,
+            is_player,
         }
     }
 
@@ -53,23 +69,44 @@ pub struct MovementTraceCollector {
     pub completed_traces: VecDeque<MovementTrace>,
     pub trace_duration: f64,
     pub max_completed_traces: usize,
+    /// The final (position, velocity) of the last trace we completed, chained into
+    /// the next trace's `expected_initial_state` so a cheater can't teleport in the
+    /// one-frame gap between proofs.
+    last_final_state: Option<(Vec2, Vec2)>,
+    /// Set by `mark_next_trace_as_first_after_reset` to override the chained state
+    /// above for exactly the next trace, forcing it back to the origin.
+    pending_initial_state_override: Option<(Vec2, Vec2)>,
+    /// Stamped onto every `MovementTrace` this collector starts - see
+    /// `MovementTrace::is_player`.
+    is_player: bool,
 }
 
 impl MovementTraceCollector {
-    pub fn new(trace_duration: f64, max_completed_traces: usize) -> Self {
+    pub fn new(trace_duration: f64, max_completed_traces: usize, is_player: bool) -> Self {
         Self {
             current_trace: None,
             completed_traces: VecDeque::new(),
             trace_duration,
             max_completed_traces,
+            last_final_state: None,
+            pending_initial_state_override: None,
+            is_player,
         }
     }
 
     pub fn start_new_trace(&mut self, timestamp: f64) {
-        self.current_trace = Some(MovementTrace::new(timestamp));
+        let expected_initial_state = self.pending_initial_state_override.take().or(self.last_final_state);
+        self.current_trace = Some(MovementTrace::new(timestamp, expected_initial_state, self.is_player));
     }
 
-    pub fn add_movement(&mut self, position: Vec2, velocity: Vec2, inputs: InputFlags, timestamp: f64) {
+    /// Forces the next trace started to declare the origin (zero position and
+    /// velocity) as its expected initial state, e.g. after the player is reset.
+    pub fn mark_next_trace_as_first_after_reset(&mut self) {
+        self.pending_initial_state_override = Some((Vec2::ZERO, Vec2::ZERO));
+        self.last_final_state = None;
+    }
+
+    pub fn add_movement(&mut self, position: Vec2, velocity: Vec2, inputs: InputFlags, timestamp: f64, delta_time: f32, frame: i32) {
         // CRITICAL FIX: Always ensure we have a trace active, even if the previous one just completed
         if self.current_trace.is_none() {
             self.start_new_trace(timestamp);
@@ -81,16 +118,17 @@ impl MovementTraceCollector {
                 velocity,
                 inputs: inputs.clone(),
                 timestamp,
-                delta_time: 0.016, // Fixed for now
+                delta_time,
+                frame,
             };
-            
+
             trace.add_step(step);
 
             // CRITICAL FIX: If trace is complete, start a new one IMMEDIATELY with this same step
             // This prevents any position changes from falling between trace boundaries
             if trace.is_complete(self.trace_duration) {
                 self.complete_current_trace();
-                
+
                 // Immediately start a new trace and add this step to it as well
                 // This ensures continuity - no position change can escape being traced
                 self.start_new_trace(timestamp);
@@ -100,7 +138,8 @@ impl MovementTraceCollector {
                         velocity,
                         inputs,
                         timestamp,
-                        delta_time: 0.016,
+                        delta_time,
+                        frame,
                     };
                     new_trace.add_step(continuation_step);
                     warn!("🔄 TRACE_BOUNDARY: Starting new trace with continuation step at ({:.1},{:.1})", position.x, position.y);
@@ -109,6 +148,32 @@ impl MovementTraceCollector {
         }
     }
 
+    /// Discards any rows collected for frames `> frame`, called when GGRS signals a
+    /// rollback so a resimulation can re-collect them without the stale, mispredicted
+    /// rows still sitting in the trace - otherwise `check_movement_constraints` would
+    /// be run over positions that never actually happened and falsely flag a cheat.
+    /// `frame` itself is the last confirmed frame (see `detect_rollback`) and is kept,
+    /// not discarded - dropping it too would leave a permanent one-frame gap in the
+    /// trace, since resimulation starts at `frame + 1` and never re-collects it.
+    pub fn rollback_to_frame(&mut self, frame: i32) {
+        if let Some(ref mut trace) = self.current_trace {
+            trace.steps.retain(|step| step.frame <= frame);
+            trace.duration = trace.steps.last().map(|step| step.timestamp - trace.start_time).unwrap_or(0.0);
+        }
+
+        // Completed traces are only ever built from confirmed frames in normal play,
+        // but defensively drop any tail trace that somehow still holds rolled-back
+        // frames rather than silently proving over bad data.
+        while let Some(last) = self.completed_traces.back() {
+            if last.steps.iter().any(|step| step.frame > frame) {
+                warn!("🔄 ROLLBACK: discarding completed trace that contains rolled-back frames > {}", frame);
+                self.completed_traces.pop_back();
+            } else {
+                break;
+            }
+        }
+    }
+
     pub fn complete_current_trace(&mut self) {
         if let Some(trace) = self.current_trace.take() {
             // Check if this trace contains any large position jumps
@@ -125,10 +190,15 @@ impl MovementTraceCollector {
             }
             
             if has_teleport {
-                warn!("🚨 TRACE WITH TELEPORT QUEUED FOR PROVING: {} steps, duration={:.3}s", 
+                warn!("🚨 TRACE WITH TELEPORT QUEUED FOR PROVING: {} steps, duration={:.3}s",
                       trace.steps.len(), trace.duration);
             }
-            
+
+            // Chain: the next trace must declare this trace's final state as its own
+            // expected initial state, closing the gap a cheater could otherwise
+            // teleport through between proofs.
+            self.last_final_state = trace.steps.last().map(|step| (step.position, step.velocity));
+
             self.completed_traces.push_back(trace);
             
             while self.completed_traces.len() > self.max_completed_traces {
@@ -141,10 +211,19 @@ impl MovementTraceCollector {
         self.completed_traces.pop_front()
     }
 
+    /// Whether a completed trace is waiting to be drained - lets a caller that needs
+    /// several collectors to line up (e.g. `npc_proof_generation_system` pairing both
+    /// NPCs) check readiness without popping one side's queue while the other isn't
+    /// ready yet.
+    pub fn has_trace_ready(&self) -> bool {
+        !self.completed_traces.is_empty()
+    }
+
 }
 
 pub fn movement_trace_collection_system(
     time: Res<Time>,
+    simulation_frame: Res<crate::SimulationFrame>,
     mut query: Query<(&Position, &Velocity, &LastInputState, &mut MovementTraceCollector), With<Player>>,
 ) {
     let current_time = time.elapsed_secs_f64();
@@ -173,15 +252,15 @@ pub fn movement_trace_collection_system(
         let pos_vec = Vec2::new(position.x as f32, position.y as f32);
         let vel_vec = Vec2::new(velocity.x as f32, velocity.y as f32);
         
-        collector.add_movement(pos_vec, vel_vec, synchronized_inputs, current_time);
+        collector.add_movement(pos_vec, vel_vec, synchronized_inputs, current_time, time.delta_secs(), simulation_frame.0);
         
         // Log any position changes (especially large ones that might be teleports)
         if let Some(trace) = &collector.current_trace {
             if let Some(last_step) = trace.steps.last() {
                 let pos_change = pos_vec.distance(last_step.position);
                 if pos_change > 10.0 {  // More than normal movement
-                    warn!("📊 LARGE POSITION CHANGE DETECTED: {:.1} pixels from ({:.1},{:.1}) to ({:.1},{:.1}) vel=({},{}) - ADDED TO TRACE", 
-                          pos_change, last_step.position.x, last_step.position.y, 
+                    warn!("📊 LARGE POSITION CHANGE DETECTED: {:.1} pixels from ({:.1},{:.1}) to ({:.1},{:.1}) vel=({},{}) - ADDED TO TRACE",
+                          pos_change, last_step.position.x, last_step.position.y,
                           pos_vec.x, pos_vec.y, velocity.x, velocity.y);
                 }
             }
@@ -189,3 +268,22 @@ pub fn movement_trace_collection_system(
     }
 }
 
+/// NPC counterpart to `movement_trace_collection_system` - same per-tick sampling,
+/// but NPCs have no `InputSource`/`LastInputState` to read, so every step just
+/// records all-false inputs (correct: Constraint 2, the only constraint that ties
+/// input flags to velocity, is gated off for `is_player = false` rows anyway).
+pub fn npc_trace_collection_system(
+    time: Res<Time>,
+    simulation_frame: Res<crate::SimulationFrame>,
+    mut query: Query<(&Position, &Velocity, &mut MovementTraceCollector), With<crate::Npc>>,
+) {
+    let current_time = time.elapsed_secs_f64();
+
+    for (position, velocity, mut collector) in &mut query {
+        let pos_vec = Vec2::new(position.x as f32, position.y as f32);
+        let vel_vec = Vec2::new(velocity.x as f32, velocity.y as f32);
+
+        collector.add_movement(pos_vec, vel_vec, InputFlags::default(), current_time, time.delta_secs(), simulation_frame.0);
+    }
+}
+
@@ -0,0 +1,163 @@
+// Peer-to-peer rollback netcode, built on the observation that `movement_system`
+// is pure deterministic integer math and the app is pinned to an exact 60 FPS
+// tick - so resimulating a handful of frames on a prediction miss reproduces the
+// exact same positions bit-for-bit on every machine. That's exactly what GGRS
+// needs, and it's what the STARK prover needs too: `detect_rollback` drops trace
+// rows for rolled-back frames so each player's trace is proven over positions
+// that actually happened. This module only runs a local `SyncTestSession`; no
+// proofs are sent to or received from a remote peer.
+use bevy::prelude::*;
+use bevy_ggrs::ggrs::{PlayerType, SessionBuilder};
+use bevy_ggrs::{ggrs, GgrsApp, GgrsPlugin, GgrsSchedule, LocalInputs, LocalPlayers, ReadInputs, Session};
+use bytemuck::{Pod, Zeroable};
+
+use crate::movement_trace::MovementTraceCollector;
+use crate::{resolve_movement_inputs, GamepadSettings, InputSource, LastInputState, Player, PlayerId, SimulationFrame};
+
+/// A player's held keys for one tick, packed into a single byte so it satisfies
+/// `ggrs::Config::Input`'s `Pod + Zeroable` bound - GGRS hashes and replays this
+/// exact byte to guarantee determinism across peers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct NetInput(pub u8);
+
+const INPUT_LEFT_BIT: u8 = 1 << 0;
+const INPUT_RIGHT_BIT: u8 = 1 << 1;
+const INPUT_UP_BIT: u8 = 1 << 2;
+const INPUT_DOWN_BIT: u8 = 1 << 3;
+
+impl NetInput {
+    pub fn from_state(state: &LastInputState) -> Self {
+        let mut bits = 0u8;
+        if state.left {
+            bits |= INPUT_LEFT_BIT;
+        }
+        if state.right {
+            bits |= INPUT_RIGHT_BIT;
+        }
+        if state.up {
+            bits |= INPUT_UP_BIT;
+        }
+        if state.down {
+            bits |= INPUT_DOWN_BIT;
+        }
+        Self(bits)
+    }
+
+    pub fn left(self) -> bool {
+        self.0 & INPUT_LEFT_BIT != 0
+    }
+
+    pub fn right(self) -> bool {
+        self.0 & INPUT_RIGHT_BIT != 0
+    }
+
+    pub fn up(self) -> bool {
+        self.0 & INPUT_UP_BIT != 0
+    }
+
+    pub fn down(self) -> bool {
+        self.0 & INPUT_DOWN_BIT != 0
+    }
+}
+
+/// GGRS's config type: inputs are a `NetInput` bitfield, players are addressed by
+/// their local `usize` handle (we don't need a richer peer address type since
+/// matchmaking/transport setup lives outside this crate).
+pub struct GgrsConfig;
+
+impl ggrs::Config for GgrsConfig {
+    type Input = NetInput;
+    type State = u8;
+    type Address = usize;
+}
+
+pub struct NetcodePlugin;
+
+impl Plugin for NetcodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GgrsPlugin::<GgrsConfig>::default())
+            .rollback_component_with_copy::<crate::Position>()
+            .rollback_component_with_copy::<crate::Velocity>()
+            .rollback_resource_with_copy::<SimulationFrame>()
+            .add_systems(ReadInputs, read_local_inputs)
+            .add_systems(
+                GgrsSchedule,
+                (
+                    detect_rollback,
+                    crate::advance_simulation_frame,
+                    crate::player_input,
+                    crate::movement_system,
+                    crate::movement_trace_collection_system,
+                )
+                    .chain(),
+            )
+            .add_systems(Startup, start_synctest_session);
+    }
+}
+
+/// Reads each locally-controlled player's own `InputSource` and packs it as this
+/// tick's `NetInput`, per `bevy_ggrs`'s `ReadInputs` contract. Both local players
+/// share one machine here, so every handle in `LocalPlayers` is resolved against the
+/// matching `PlayerId` entity rather than one shared keyboard read - otherwise both
+/// players would move identically regardless of which keys either of them pressed.
+fn read_local_inputs(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    gamepad_settings: Res<GamepadSettings>,
+    players: Query<(&PlayerId, &InputSource)>,
+    local_players: Res<LocalPlayers>,
+) {
+    let mut local_inputs = std::collections::HashMap::new();
+    for handle in &local_players.0 {
+        let Some((_, source)) = players.iter().find(|(id, _)| id.0 == *handle) else {
+            continue;
+        };
+        let (left, right, up, down) = resolve_movement_inputs(source, &keyboard_input, &gamepads, &gamepad_settings);
+        local_inputs.insert(*handle, NetInput::from_state(&LastInputState { left, right, up, down }));
+    }
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+/// Starts a local `SyncTestSession` - GGRS resimulates every frame a configurable
+/// number of times and compares checksums against itself, catching any
+/// nondeterminism in `player_input`/`movement_system` without needing a second
+/// machine or a real network session. A real P2P session is a deployment concern
+/// left to whatever binary embeds this crate in an actual match.
+fn start_synctest_session(mut commands: Commands) {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(2)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)
+        .expect("adding local player 0 to a fresh SyncTestSession cannot fail")
+        .add_player(PlayerType::Local, 1)
+        .expect("adding local player 1 to a fresh SyncTestSession cannot fail")
+        .start_synctest_session()
+        .expect("SyncTestSession config above is internally consistent");
+
+    commands.insert_resource(Session::SyncTestSession(session));
+    commands.insert_resource(LocalPlayers(vec![0, 1]));
+}
+
+/// Runs ahead of `player_input`/`movement_system`/`movement_trace_collection_system`
+/// in `GgrsSchedule`. When GGRS has rewound `SimulationFrame` to resimulate mispredicted
+/// frames, the trace collector must forget any rows it already recorded for those
+/// frames - otherwise a resimulated (corrected) position would be appended alongside
+/// the stale mispredicted one, and `check_movement_constraints` would see a trace that
+/// never actually happened and falsely flag a cheat.
+fn detect_rollback(
+    simulation_frame: Res<SimulationFrame>,
+    mut query: Query<&mut MovementTraceCollector, With<Player>>,
+    mut last_seen_frame: Local<Option<i32>>,
+) {
+    if let Some(last) = *last_seen_frame {
+        if simulation_frame.0 < last {
+            for mut collector in &mut query {
+                collector.rollback_to_frame(simulation_frame.0);
+            }
+        }
+    }
+    *last_seen_frame = Some(simulation_frame.0);
+}
@@ -0,0 +1,116 @@
+// Batches many independently-generated proofs into one artifact per batch, so a
+// server verifying a whole session doesn't need to store (or re-check) every
+// constituent proof on its own.
+//
+// This is NOT succinct proof recursion. Real recursive folding would need a
+// dedicated recursive-verifier AIR - a STARK that proves "I checked N STARK
+// proofs" - which doesn't exist anywhere in this codebase and would be a project
+// of its own. What this gives a server instead: one artifact per batch, with a
+// commitment hash binding every constituent's identity (trace hash, public inputs)
+// and verify outcome, so it only needs to store/compare one hash per batch rather
+// than every individual proof. `flush` still re-verifies each constituent via the
+// live `MovementProver` along the way - this folds their *result* into one
+// artifact, not their verification *cost*.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+
+use bevy::prelude::Resource;
+
+use crate::prover::{ProvedArtifact, ProverBackend};
+
+/// One proof buffered for the next batch - everything `ProofAggregator::flush`
+/// needs to fold it in and independently re-check it.
+pub struct BufferedProof {
+    pub player_id: usize,
+    pub trace_hash: u64,
+    pub public_inputs: Vec<u64>,
+    pub artifact: ProvedArtifact,
+}
+
+/// The result of folding a batch of `BufferedProof`s.
+#[derive(Debug, Clone)]
+pub struct AggregateArtifact {
+    pub commitment: u64,
+    pub constituent_count: usize,
+    pub all_verified: bool,
+    pub bytes: Vec<u8>,
+}
+
+impl AggregateArtifact {
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+/// Buffers completed proofs until there are `batch_size` of them (or a flush is
+/// forced, e.g. on session end), then folds the whole buffer into one
+/// `AggregateArtifact`.
+#[derive(Resource)]
+pub struct ProofAggregator {
+    pending: Vec<BufferedProof>,
+    pub batch_size: usize,
+}
+
+impl Default for ProofAggregator {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+            batch_size: 16,
+        }
+    }
+}
+
+impl ProofAggregator {
+    pub fn push(&mut self, proof: BufferedProof) {
+        self.pending.push(proof);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.pending.len() >= self.batch_size.max(1)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Folds every buffered proof into one `AggregateArtifact`, re-verifying each
+    /// constituent against `backend`, and clears the buffer. Returns `None` if
+    /// there's nothing buffered (e.g. flushing an already-empty aggregator on
+    /// session end).
+    pub fn flush(&mut self, backend: ProverBackend) -> Option<(AggregateArtifact, f64)> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let prover = backend.build();
+        let start = Instant::now();
+
+        let mut hasher = DefaultHasher::new();
+        let mut all_verified = true;
+        for buffered in &self.pending {
+            buffered.player_id.hash(&mut hasher);
+            buffered.trace_hash.hash(&mut hasher);
+            buffered.public_inputs.hash(&mut hasher);
+            let verified = prover.verify(&buffered.artifact).is_ok();
+            verified.hash(&mut hasher);
+            all_verified &= verified;
+        }
+        let commitment = hasher.finish();
+        let verify_time_ms = start.elapsed().as_millis() as f64;
+
+        let mut bytes = commitment.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&(self.pending.len() as u32).to_le_bytes());
+        bytes.push(all_verified as u8);
+
+        let artifact = AggregateArtifact {
+            commitment,
+            constituent_count: self.pending.len(),
+            all_verified,
+            bytes,
+        };
+
+        self.pending.clear();
+        Some((artifact, verify_time_ms))
+    }
+}
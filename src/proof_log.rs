@@ -0,0 +1,342 @@
+// Append-only, replayable log of generated proofs, so a referee can re-check a
+// match's anti-cheat proofs offline instead of trusting the live game's in-process
+// `MovementProver::verify` call - the same reason a SAT solver emits a checkable
+// proof trace rather than just asserting "unsat". Framed similarly to
+// `trace_codec`'s wire format: magic + version header, then length-prefixed
+// records, so the file can be streamed without loading it all into memory.
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::Resource;
+
+use crate::movement_trace::MovementTrace;
+use crate::proof_system::ProofSystemSettings;
+use crate::prover::ProverBackend;
+use crate::trace_codec;
+
+const HEADER_MAGIC: [u8; 4] = *b"PLOG";
+const HEADER_VERSION: u8 = 1;
+const HEADER_LEN: usize = 4 + 1 + 4 * 6; // magic + version + 6 little-endian f32s
+
+/// Schema version stamped on every record - bump this if a field is added/removed
+/// so `verify_log` can tell an old log apart from a corrupt one.
+///
+/// v2 added `player_id`, so `verify_log` can check that one player's record picks
+/// up from the previous record's declared final position instead of only
+/// re-verifying each record's own proof in isolation.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Whether `proof_generation_system` writes successful proofs to disk, and where.
+/// Off by default so a plain dev/CI run doesn't silently start accumulating a proof
+/// log file.
+#[derive(Resource)]
+pub struct ProofLogConfig {
+    pub enabled: bool,
+    pub path: PathBuf,
+}
+
+impl Default for ProofLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("proof_log.bin"),
+        }
+    }
+}
+
+/// One successful proof as persisted to the log - everything `verify_log` needs to
+/// re-check it offline, independent of anything the live game still remembers.
+struct ProofLogRecord {
+    schema_version: u32,
+    backend: ProverBackend,
+    /// Which `PlayerId` this trace belongs to - lets `verify_log` group records by
+    /// player and check each one picks up from the previous one's declared final
+    /// position (see the cross-trace check in `verify_log`).
+    player_id: u64,
+    trace_hash: u64,
+    public_inputs: Vec<u64>,
+    proof_bytes: Vec<u8>,
+    timestamp: f64,
+}
+
+fn backend_to_byte(backend: ProverBackend) -> u8 {
+    match backend {
+        ProverBackend::Plonky3Stark => 0,
+        ProverBackend::MockNativeReplay => 1,
+    }
+}
+
+fn backend_from_byte(byte: u8) -> Result<ProverBackend, String> {
+    match byte {
+        0 => Ok(ProverBackend::Plonky3Stark),
+        1 => Ok(ProverBackend::MockNativeReplay),
+        other => Err(format!("unknown prover backend tag {other}")),
+    }
+}
+
+/// One record's outcome from `verify_log` - a plain `Result<(), String>` can't
+/// distinguish "this proof is invalid" from "this backend never produced a
+/// cryptographic proof to re-check", and collapsing those into one generic
+/// verification failure is exactly what misled a `MockNativeReplay` session's log
+/// before this type existed.
+#[derive(Debug)]
+pub enum LogVerdict {
+    Verified,
+    /// `MockNativeReplay` artifacts are a same-process fingerprint, not a proof -
+    /// there's nothing for a standalone verifier to independently re-check.
+    Skipped(String),
+    Failed(String),
+}
+
+/// Hashes `trace`'s on-the-wire encoding (`trace_codec::to_bytes`) so a log record
+/// can be tied to a specific trace without the log needing to store the trace
+/// itself.
+pub fn hash_trace(trace: &MovementTrace) -> u64 {
+    let bytes = trace_codec::to_bytes(trace);
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn write_header(file: &mut File, settings: &ProofSystemSettings) -> io::Result<()> {
+    file.write_all(&HEADER_MAGIC)?;
+    file.write_all(&[HEADER_VERSION])?;
+    file.write_all(&settings.movement_speed.to_le_bytes())?;
+    file.write_all(&settings.game_bounds.0.to_le_bytes())?;
+    file.write_all(&settings.game_bounds.1.to_le_bytes())?;
+    file.write_all(&settings.game_bounds.2.to_le_bytes())?;
+    file.write_all(&settings.game_bounds.3.to_le_bytes())?;
+    file.write_all(&settings.delta_time.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_header(bytes: &[u8]) -> Result<(ProofSystemSettings, usize), String> {
+    if bytes.len() < HEADER_LEN {
+        return Err("proof log truncated before header".to_string());
+    }
+    if &bytes[0..4] != &HEADER_MAGIC[..] {
+        return Err("not a proof log file (bad magic)".to_string());
+    }
+    let version = bytes[4];
+    if version != HEADER_VERSION {
+        return Err(format!("unsupported proof log version {version}"));
+    }
+
+    let mut offset = 5;
+    let mut read_f32 = || {
+        let value = f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        value
+    };
+    let movement_speed = read_f32();
+    let min_x = read_f32();
+    let max_x = read_f32();
+    let min_y = read_f32();
+    let max_y = read_f32();
+    let delta_time = read_f32();
+
+    let settings = ProofSystemSettings {
+        movement_speed,
+        game_bounds: (min_x, max_x, min_y, max_y),
+        delta_time,
+        ..ProofSystemSettings::default()
+    };
+
+    Ok((settings, offset))
+}
+
+fn write_record(file: &mut File, record: &ProofLogRecord) -> io::Result<()> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&record.schema_version.to_le_bytes());
+    body.push(backend_to_byte(record.backend));
+    body.extend_from_slice(&record.player_id.to_le_bytes());
+    body.extend_from_slice(&record.trace_hash.to_le_bytes());
+    body.extend_from_slice(&(record.public_inputs.len() as u32).to_le_bytes());
+    for value in &record.public_inputs {
+        body.extend_from_slice(&value.to_le_bytes());
+    }
+    body.extend_from_slice(&(record.proof_bytes.len() as u32).to_le_bytes());
+    body.extend_from_slice(&record.proof_bytes);
+    body.extend_from_slice(&record.timestamp.to_le_bytes());
+
+    file.write_all(&(body.len() as u32).to_le_bytes())?;
+    file.write_all(&body)?;
+    Ok(())
+}
+
+/// Parses one length-prefixed record starting at `offset`, returning it along with
+/// the offset of the next record.
+fn read_record(bytes: &[u8], offset: usize) -> Result<(ProofLogRecord, usize), String> {
+    if offset + 4 > bytes.len() {
+        return Err("proof log truncated before record length".to_string());
+    }
+    let body_len = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+    let mut pos = offset + 4;
+    let end = pos + body_len;
+    if end > bytes.len() {
+        return Err("proof log truncated mid-record".to_string());
+    }
+
+    let schema_version = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap());
+    pos += 4;
+    let backend = backend_from_byte(bytes[pos])?;
+    pos += 1;
+    let player_id = u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap());
+    pos += 8;
+    let trace_hash = u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap());
+    pos += 8;
+    let public_inputs_len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+    pos += 4;
+    let mut public_inputs = Vec::with_capacity(public_inputs_len);
+    for _ in 0..public_inputs_len {
+        public_inputs.push(u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap()));
+        pos += 8;
+    }
+    let proof_bytes_len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+    pos += 4;
+    let proof_bytes = bytes[pos..pos + proof_bytes_len].to_vec();
+    pos += proof_bytes_len;
+    let timestamp = f64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap());
+    pos += 8;
+
+    if pos != end {
+        return Err("proof log record length did not match its contents".to_string());
+    }
+
+    Ok((
+        ProofLogRecord {
+            schema_version,
+            backend,
+            player_id,
+            trace_hash,
+            public_inputs,
+            proof_bytes,
+            timestamp,
+        },
+        end,
+    ))
+}
+
+/// Appends one successful proof to the log at `path`, writing the header first if
+/// the file doesn't exist yet. `trace_hash`/`public_inputs` are recomputed by the
+/// caller from the trace the artifact was proven over (see `hash_trace` and
+/// `movement_air::trace_public_values`) - this module only owns the framing.
+/// `backend` is stamped on the record so `verify_log` knows how (or whether) to
+/// re-check it later - a `MockNativeReplay` artifact is a same-process fingerprint,
+/// not a Plonky3 proof, and was never going to deserialize as one. `player_id` (a
+/// `PlayerId`'s inner value) is what lets `verify_log` group records back into
+/// per-player chains for its cross-trace continuity check.
+pub fn append_proof(
+    path: &Path,
+    settings: &ProofSystemSettings,
+    backend: ProverBackend,
+    player_id: u64,
+    trace_hash: u64,
+    public_inputs: &[u64],
+    proof_bytes: &[u8],
+    timestamp: f64,
+) -> io::Result<()> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        write_header(&mut file, settings)?;
+    }
+
+    write_record(
+        &mut file,
+        &ProofLogRecord {
+            schema_version: SCHEMA_VERSION,
+            backend,
+            player_id,
+            trace_hash,
+            public_inputs: public_inputs.to_vec(),
+            proof_bytes: proof_bytes.to_vec(),
+            timestamp,
+        },
+    )
+}
+
+/// Standalone verification entry point: rebuilds the exact `MovementAir` +
+/// `StarkConfig` described by the log's own header and re-runs `verify` on every
+/// `Plonky3Stark` record, in file order, with no dependency on the live game process
+/// that wrote it. `MockNativeReplay` records are reported as `Skipped` rather than
+/// fed through the Plonky3 deserializer they were never produced for. Before
+/// either of those, every record is checked against `check_cross_trace_chain` - a
+/// record whose own proof is individually valid but whose trace doesn't pick up
+/// from the player's previous one is exactly the teleport-in-the-gap this proof
+/// chain exists to catch, so it's checked even for backends with nothing else to
+/// verify. Returns one verdict per record.
+pub fn verify_log(path: &Path) -> Result<Vec<LogVerdict>, String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .map_err(|e| format!("failed to open {}: {e}", path.display()))?
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+    let (settings, mut offset) = read_header(&bytes)?;
+    let mut results = Vec::new();
+    let mut last_final_position_by_player: std::collections::HashMap<u64, (u64, u64)> = std::collections::HashMap::new();
+
+    while offset < bytes.len() {
+        let (record, next_offset) = read_record(&bytes, offset)?;
+        offset = next_offset;
+
+        let verdict = if record.schema_version != SCHEMA_VERSION {
+            LogVerdict::Failed(format!("unsupported record schema version {}", record.schema_version))
+        } else if let Err(e) = check_cross_trace_chain(&record, &mut last_final_position_by_player) {
+            LogVerdict::Failed(e)
+        } else {
+            match record.backend {
+                ProverBackend::MockNativeReplay => LogVerdict::Skipped(
+                    "MockNativeReplay artifact is a same-process fingerprint, not independently verifiable".to_string(),
+                ),
+                ProverBackend::Plonky3Stark => {
+                    match crate::prover::verify_artifact_with_settings(&settings, &record.proof_bytes) {
+                        Ok(()) => LogVerdict::Verified,
+                        Err(e) => LogVerdict::Failed(e.to_string()),
+                    }
+                }
+            }
+        };
+        results.push(verdict);
+    }
+
+    Ok(results)
+}
+
+/// Checks that `record`'s declared initial position (agent slot 0 of its
+/// `public_inputs`, per `movement_air::trace_public_values`'s layout) equals the
+/// same player's previous record's declared final position - closing the gap a
+/// modified client could otherwise hide a teleport in, since `expected_initial_state`
+/// is entirely self-reported and a `generate_movement_trace_matrix` panic only ever
+/// catches a mismatch the *same* (possibly-cheating) process introduced. A player's
+/// very first record, or one whose initial position is the origin (the state a
+/// cheat-detection reset puts a player back in), is exempt - there's no prior proof
+/// to have continued from. Updates `last_final_position_by_player` with this
+/// record's final position regardless of outcome, so a later record is still
+/// checked against the right value.
+fn check_cross_trace_chain(
+    record: &ProofLogRecord,
+    last_final_position_by_player: &mut std::collections::HashMap<u64, (u64, u64)>,
+) -> Result<(), String> {
+    if record.public_inputs.len() < 4 {
+        return Err("record has too few public values to check trace chaining against".to_string());
+    }
+    let initial_position = (record.public_inputs[0], record.public_inputs[1]);
+    let final_position = (record.public_inputs[2], record.public_inputs[3]);
+    let origin = crate::movement_air::encode_position(0.0);
+
+    let result = match last_final_position_by_player.get(&record.player_id) {
+        Some(&expected) if expected != initial_position && initial_position != (origin, origin) => Err(format!(
+            "player {}'s trace starts at ({}, {}) but the previous proof for this player ended at ({}, {}) - possible teleport between proofs",
+            record.player_id, initial_position.0, initial_position.1, expected.0, expected.1
+        )),
+        _ => Ok(()),
+    };
+
+    last_final_position_by_player.insert(record.player_id, final_position);
+    result
+}
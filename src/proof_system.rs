@@ -1,42 +1,38 @@
-use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::DuplexChallenger;
-use p3_commit::ExtensionMmcs;
-use p3_dft::Radix2DitParallel;
-use p3_field::extension::BinomialExtensionField;
-use p3_fri::{TwoAdicFriPcs, create_test_fri_params};
-use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{StarkConfig, prove, verify};
-use p3_matrix::Matrix;
-use rand::SeedableRng;
-use rand::rngs::SmallRng;
 use bevy::prelude::*;
 use bevy::tasks::{AsyncComputeTaskPool, Task};
+use std::collections::VecDeque;
 use std::time::Instant;
 use futures_lite::future;
 
-use crate::movement_air::{MovementAir, generate_movement_trace_matrix, next_power_of_2};
+use crate::check_constraints::check_movement_constraints;
+use crate::movement_air::{generate_movement_trace_matrix, next_power_of_2};
 use crate::movement_trace::{MovementTrace, MovementTraceCollector};
-use crate::Player;
-
-// Type aliases for our STARK configuration
-type Val = BabyBear;
-type Perm = Poseidon2BabyBear<16>;
-type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
-type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
-type ValMmcs = MerkleTreeMmcs<<Val as p3_field::Field>::Packing, <Val as p3_field::Field>::Packing, MyHash, MyCompress, 8>;
-type Challenge = BinomialExtensionField<Val, 4>;
-type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
-type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
-type Dft = Radix2DitParallel<Val>;
-type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
-type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+use crate::prover::ProverBackend;
+use crate::{CheatPolicy, HudLog, Npc, Player, PlayerId};
+
+// The field type `check_movement_constraints` (and, through it, every prover
+// backend) encodes a trace's trace matrix in. `MovementAir`'s column layout is
+// specific to this field, so it isn't part of what `ProverBackend` makes pluggable.
+type Val = p3_baby_bear::BabyBear;
 
 #[derive(Resource)]
 pub struct ProofSystemSettings {
     pub movement_speed: f32,
     pub game_bounds: (f32, f32, f32, f32),
     pub delta_time: f32,
+    /// Caps `ProofScheduler`'s concurrent `AsyncComputeTaskPool` jobs, across every
+    /// player - without this, a burst of movement can spawn hundreds of concurrent
+    /// proving tasks and starve the frame loop.
+    pub max_in_flight_proofs: usize,
+    /// Caps how many completed-but-not-yet-proving traces `ProofScheduler` will hold
+    /// before it starts dropping them (counted in `ProofStats::rejected_count`).
+    pub max_queued_proofs: usize,
+    /// Which structured format `proof_generation_system` exports each completed
+    /// proof's metrics in - see `metrics_export`.
+    pub metrics_format: crate::metrics_export::MetricsFormat,
+    /// How many completed proofs `ProofAggregator` buffers before folding them
+    /// into one `AggregateArtifact`.
+    pub aggregate_batch_size: usize,
 }
 
 impl Default for ProofSystemSettings {
@@ -45,30 +41,68 @@ impl Default for ProofSystemSettings {
             movement_speed: 200.0, // pixels per second
             game_bounds: (-400.0, 400.0, -300.0, 300.0), // Window bounds
             delta_time: 1.0 / 60.0, // 60 FPS
+            max_in_flight_proofs: 4,
+            max_queued_proofs: 64,
+            metrics_format: crate::metrics_export::MetricsFormat::default(),
+            aggregate_batch_size: 16,
         }
     }
 }
 
-fn create_stark_config() -> (MyConfig, MovementAir) {
-    let mut rng = SmallRng::seed_from_u64(42); // Fixed seed for reproducibility
-    let perm = Perm::new_from_rng_128(&mut rng);
-    let hash = MyHash::new(perm.clone());
-    let compress = MyCompress::new(perm.clone());
-    let val_mmcs = ValMmcs::new(hash, compress);
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft::default();
-    
-    let fri_params = create_test_fri_params(challenge_mmcs, 2);
-    let pcs = Pcs::new(dft, val_mmcs, fri_params);
-    let challenger = Challenger::new(perm);
-    
-    let config = MyConfig::new(pcs, challenger);
-    
-    // Game configuration - using defaults for now
-    let settings = ProofSystemSettings::default();
-    let air = MovementAir::new(settings.movement_speed, settings.game_bounds, settings.delta_time);
-    
-    (config, air)
+/// A trace waiting in `ProofScheduler` to be proven, tagged with which player
+/// entity it belongs to (so a completed task's result lands back in the right
+/// `ProofGenerator`) and whether it jumps the queue.
+struct PendingProof {
+    player: Entity,
+    player_id: PlayerId,
+    trace: MovementTrace,
+    sanctioned: bool,
+    /// Set when the trace already looked like a teleport (`max_jump > 50.0`) before
+    /// proving even started - these jump ahead of routine traces so an obvious
+    /// cheat gets flagged without waiting behind a backlog of honest play.
+    suspicious: bool,
+}
+
+/// Bounded, prioritized scheduler for proof-generation tasks, modeled on a proving
+/// service's task manager: a hard cap on concurrent jobs, a pending queue for the
+/// rest, and a priority lane so suspicious traces don't wait behind routine ones.
+/// Without this, `proof_generation_system` would spawn one `AsyncComputeTaskPool`
+/// task per completed trace with no limit, and a burst of movement (or a
+/// deliberately chatty client) could starve the frame loop.
+#[derive(Resource, Default)]
+pub struct ProofScheduler {
+    urgent: VecDeque<PendingProof>,
+    routine: VecDeque<PendingProof>,
+    pub in_flight: usize,
+    pub max_in_flight: usize,
+    pub max_queued: usize,
+    pub rejected_count: usize,
+}
+
+impl ProofScheduler {
+    pub fn queue_depth(&self) -> usize {
+        self.urgent.len() + self.routine.len()
+    }
+
+    fn enqueue(&mut self, pending: PendingProof) {
+        if self.queue_depth() >= self.max_queued {
+            self.rejected_count += 1;
+            warn!(
+                "⏳ PROOF QUEUE FULL: dropping trace for player {} ({} steps) - backpressure",
+                pending.player_id.0, pending.trace.steps.len()
+            );
+            return;
+        }
+        if pending.suspicious {
+            self.urgent.push_back(pending);
+        } else {
+            self.routine.push_back(pending);
+        }
+    }
+
+    fn pop_next(&mut self) -> Option<PendingProof> {
+        self.urgent.pop_front().or_else(|| self.routine.pop_front())
+    }
 }
 
 #[derive(Component)]
@@ -83,9 +117,86 @@ pub struct ProofResult {
     pub result: Result<(Vec<u8>, usize), String>, // (proof_bytes, size) or error
     pub generation_time_ms: f64,
     pub verification_time_ms: f64,
+    /// "Row N: <constraint message>" lines from `check_movement_constraints`, so the
+    /// HUD can show the real offending constraint instead of only a pass/fail verdict.
+    pub diagnostics: Vec<String>,
+    /// Identifies the trace this result was proven from, computed before the trace
+    /// was moved into the async task - carried back out so a successful proof can
+    /// still be appended to `proof_log` without re-deriving it from the (by then
+    /// consumed) trace.
+    pub trace_hash: u64,
+    pub public_inputs: Vec<u64>,
+}
+
+
+
+/// How many of the most recent generation/verification samples `ProofStats` keeps
+/// for its percentile summary - old enough samples are dropped so tail latency
+/// reflects recent play, not a match's entire history.
+const TIMING_SAMPLE_CAPACITY: usize = 256;
+
+/// A benchmark-harness-style summary of a set of timing samples (milliseconds):
+/// min/max/mean alongside median and quartiles (nearest-rank), inter-quartile
+/// range, standard deviation, p95/p99, and a Winsorized mean that clamps samples
+/// outside the 5th/95th percentile band before averaging - so one GC/scheduling
+/// hiccup doesn't dominate the displayed "Avg Gen".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimingSummary {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub iqr: f64,
+    pub stddev: f64,
+    pub winsorized_mean: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+/// Nearest-rank percentile of an already-sorted slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil().max(1.0) as usize;
+    sorted[rank.min(sorted.len()) - 1]
 }
 
+fn summarize_samples(samples: &VecDeque<f64>) -> TimingSummary {
+    if samples.is_empty() {
+        return TimingSummary::default();
+    }
+
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len();
 
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+    let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+
+    let p95 = percentile(&sorted, 95.0);
+    let lower_band = percentile(&sorted, 5.0);
+    let winsorized_mean = sorted.iter().map(|&v| v.clamp(lower_band, p95)).sum::<f64>() / n as f64;
+
+    let q1 = percentile(&sorted, 25.0);
+    let q3 = percentile(&sorted, 75.0);
+
+    TimingSummary {
+        min: sorted[0],
+        max: sorted[n - 1],
+        mean,
+        median: percentile(&sorted, 50.0),
+        q1,
+        q3,
+        iqr: q3 - q1,
+        stddev: variance.sqrt(),
+        winsorized_mean,
+        p95,
+        p99: percentile(&sorted, 99.0),
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct ProofStats {
@@ -94,6 +205,28 @@ pub struct ProofStats {
     pub total_verification_time_ms: f64,
     pub successful_verifications: usize,
     pub failed_verifications: usize,
+    /// Snapshot of `ProofScheduler::queue_depth` as of the last `proof_generation_system`
+    /// run, so `ProofStatsText` can show backpressure without querying the scheduler
+    /// resource directly.
+    pub queue_depth: usize,
+    /// Snapshot of `ProofScheduler::rejected_count` - traces dropped because the
+    /// queue was already full.
+    pub rejected_count: usize,
+    /// How many `ProofAggregator::flush` batches have completed so far.
+    pub total_aggregates: usize,
+    /// Total individual proofs folded across every completed aggregate batch.
+    pub total_constituents_folded: usize,
+    /// `AggregateArtifact::size` of the most recently completed batch.
+    pub last_aggregate_size: usize,
+    /// How long the most recent aggregate batch took to fold (re-verifying every
+    /// constituent along the way).
+    pub last_aggregate_verify_time_ms: f64,
+    /// Ring buffers of recent generation/verification times, for `TimingSummary` -
+    /// `total_generation_time_ms`/`total_verification_time_ms` only let us report a
+    /// running average, which hides tail latency (a single 400ms proof is invisible
+    /// next to the mean).
+    generation_samples: VecDeque<f64>,
+    verification_samples: VecDeque<f64>,
 }
 
 impl ProofStats {
@@ -113,6 +246,36 @@ impl ProofStats {
             0.0
         }
     }
+
+    fn push_sample(buf: &mut VecDeque<f64>, value: f64) {
+        buf.push_back(value);
+        while buf.len() > TIMING_SAMPLE_CAPACITY {
+            buf.pop_front();
+        }
+    }
+
+    pub fn record_generation_sample(&mut self, value_ms: f64) {
+        Self::push_sample(&mut self.generation_samples, value_ms);
+    }
+
+    pub fn record_verification_sample(&mut self, value_ms: f64) {
+        Self::push_sample(&mut self.verification_samples, value_ms);
+    }
+
+    pub fn generation_summary(&self) -> TimingSummary {
+        summarize_samples(&self.generation_samples)
+    }
+
+    pub fn verification_summary(&self) -> TimingSummary {
+        summarize_samples(&self.verification_samples)
+    }
+
+    pub fn record_aggregate(&mut self, constituents: usize, aggregate_size: usize, verify_time_ms: f64) {
+        self.total_aggregates += 1;
+        self.total_constituents_folded += constituents;
+        self.last_aggregate_size = aggregate_size;
+        self.last_aggregate_verify_time_ms = verify_time_ms;
+    }
 }
 
 impl Default for ProofGenerator {
@@ -127,83 +290,235 @@ impl Default for ProofGenerator {
 
 pub fn proof_generation_system(
     time: Res<Time>,
-    mut query: Query<(&mut MovementTraceCollector, &mut ProofGenerator), With<Player>>,
+    cheat_policy: Res<CheatPolicy>,
+    prover_backend: Res<ProverBackend>,
+    settings: Res<ProofSystemSettings>,
+    log_config: Res<crate::proof_log::ProofLogConfig>,
+    metrics_config: Res<crate::metrics_export::MetricsExportConfig>,
+    mut metrics_state: ResMut<crate::metrics_export::MetricsExportState>,
+    mut aggregator: ResMut<crate::proof_aggregation::ProofAggregator>,
+    mut scheduler: ResMut<ProofScheduler>,
+    mut hud_log: ResMut<HudLog>,
+    mut query: Query<(Entity, &PlayerId, &mut MovementTraceCollector, &mut ProofGenerator), With<Player>>,
     mut commands: Commands,
 ) {
-    let _current_time = time.elapsed_secs_f64();
+    let current_time = time.elapsed_secs_f64();
+    scheduler.max_in_flight = settings.max_in_flight_proofs;
+    scheduler.max_queued = settings.max_queued_proofs;
+    aggregator.batch_size = settings.aggregate_batch_size;
 
-    for (mut collector, mut proof_gen) in &mut query {
-        // Check for completed traces to prove and start async tasks
+    // Drain every player's completed traces into the scheduler's pending queue
+    // instead of spawning a task for each one immediately - this is what makes the
+    // in-flight cap apply across the whole match rather than per player.
+    for (entity, player_id, mut collector, _) in &mut query {
         while let Some(trace) = collector.get_next_trace_for_proving() {
-            if trace.steps.len() > 1 {
-                // Check if this trace contains teleportation
-                let mut max_jump: f32 = 0.0;
-                for i in 1..trace.steps.len() {
-                    let distance = trace.steps[i-1].position.distance(trace.steps[i].position);
-                    max_jump = max_jump.max(distance);
-                }
-                
-                if max_jump > 50.0 {
-                    warn!("🚀 PROVING TRACE WITH TELEPORT: {} steps, max_jump={:.1} pixels", trace.steps.len(), max_jump);
-                } else {
-                    info!("🚀 Starting async proof generation for trace with {} steps", trace.steps.len());
-                }
-                
-                // Start async proof generation task
-                let task_pool = AsyncComputeTaskPool::get();
-                let trace_clone = trace.clone();
-                
-                #[allow(unused_must_use)]
-                let task = task_pool.spawn(async move {
-                    let generation_start = Instant::now();
-                    
-                    // Generate proof on background thread
-                    let (result, verification_time) = generate_proof_async(&trace_clone).await;
-                    let generation_time = generation_start.elapsed().as_millis() as f64;
-                    
-                    ProofResult {
-                        result,
-                        generation_time_ms: generation_time,
-                        verification_time_ms: verification_time,
-                    }
-                });
-                
-                proof_gen.active_tasks.push(task);
+            if trace.steps.len() <= 1 {
+                continue;
+            }
+
+            // Check if this trace contains teleportation
+            let mut max_jump: f32 = 0.0;
+            for i in 1..trace.steps.len() {
+                let distance = trace.steps[i - 1].position.distance(trace.steps[i].position);
+                max_jump = max_jump.max(distance);
             }
+            let suspicious = max_jump > 50.0;
+
+            if suspicious {
+                warn!("🚀 QUEUEING SUSPICIOUS TRACE: {} steps, max_jump={:.1} pixels", trace.steps.len(), max_jump);
+            } else {
+                info!("🚀 Queueing trace for proving: {} steps", trace.steps.len());
+            }
+
+            scheduler.enqueue(PendingProof {
+                player: entity,
+                player_id: *player_id,
+                trace,
+                sanctioned: cheat_policy.is_sanctioned(*player_id),
+                suspicious,
+            });
         }
+    }
+
+    // Pull from the scheduler while there's a free in-flight slot, spawning onto
+    // whichever player entity's `ProofGenerator` owns the trace. Suspicious traces
+    // were queued ahead of routine ones, so an obvious teleport gets proven (and
+    // potentially flagged) without waiting behind a backlog of honest play.
+    while scheduler.in_flight < scheduler.max_in_flight {
+        let Some(pending) = scheduler.pop_next() else {
+            break;
+        };
+        let Ok((_, _, _, mut proof_gen)) = query.get_mut(pending.player) else {
+            // The entity despawned while its trace was queued - nothing to attribute
+            // the result to, so just drop it.
+            continue;
+        };
+
+        let task_pool = AsyncComputeTaskPool::get();
+        let backend = *prover_backend;
+        let sanctioned = pending.sanctioned;
+        let trace = pending.trace;
+
+        // Computed before the trace moves into the task below, so a successful
+        // result can still be appended to `proof_log` afterwards without the
+        // (by-then consumed) trace.
+        let trace_hash = crate::proof_log::hash_trace(&trace);
+        let public_inputs = crate::movement_air::trace_public_values::<Val>(std::slice::from_ref(&trace))
+            .iter()
+            .map(|value| p3_field::PrimeField64::as_canonical_u64(value))
+            .collect::<Vec<_>>();
+
+        #[allow(unused_must_use)]
+        let task = task_pool.spawn(async move {
+            let generation_start = Instant::now();
+
+            // Generate proof on background thread
+            let (result, verification_time, diagnostics) = generate_proof_async(&trace, sanctioned, backend).await;
+            let generation_time = generation_start.elapsed().as_millis() as f64;
+
+            ProofResult {
+                result,
+                generation_time_ms: generation_time,
+                verification_time_ms: verification_time,
+                diagnostics,
+                trace_hash,
+                public_inputs,
+            }
+        });
+
+        proof_gen.active_tasks.push(task);
+        scheduler.in_flight += 1;
+    }
+
+    let queue_depth = scheduler.queue_depth();
+    let rejected_count = scheduler.rejected_count;
+
+    // Check for completed async tasks (non-blocking)
+    for (_, player_id, _, mut proof_gen) in &mut query {
+        proof_gen.stats.queue_depth = queue_depth;
+        proof_gen.stats.rejected_count = rejected_count;
 
-        // Check for completed async tasks (non-blocking)
         let mut i = 0;
         while i < proof_gen.active_tasks.len() {
             if let Some(result) = future::block_on(future::poll_once(&mut proof_gen.active_tasks[i])) {
                 // Task completed, remove it and process result
                 let _ = proof_gen.active_tasks.remove(i);
-                
+                scheduler.in_flight = scheduler.in_flight.saturating_sub(1);
+
+                // Surface the specific offending constraint(s), if any were found,
+                // before the pass/fail summary line - this is what makes a caught
+                // cheat observable on the HUD rather than only in the terminal.
+                for diagnostic in &result.diagnostics {
+                    hud_log.push(format!("Player {}: {}", player_id.0, diagnostic));
+                }
+
                 match result.result {
-                    Ok((_proof_bytes, proof_size)) => {
-                        info!("✅ Proof generated successfully in {:.2}ms, verified in {:.2}ms, size: {} bytes", 
+                    Ok((proof_bytes, proof_size)) => {
+                        info!("✅ Proof generated successfully in {:.2}ms, verified in {:.2}ms, size: {} bytes",
                               result.generation_time_ms, result.verification_time_ms, proof_size);
-                        
+
                         // Update statistics
                         proof_gen.stats.total_proofs_generated += 1;
                         proof_gen.stats.total_generation_time_ms += result.generation_time_ms;
                         proof_gen.stats.total_verification_time_ms += result.verification_time_ms;
                         proof_gen.stats.successful_verifications += 1;
-                        
+                        proof_gen.stats.record_generation_sample(result.generation_time_ms);
+                        proof_gen.stats.record_verification_sample(result.verification_time_ms);
+
                         proof_gen.completed_count += 1;
+
+                        if log_config.enabled && !proof_bytes.is_empty() {
+                            if let Err(e) = crate::proof_log::append_proof(
+                                &log_config.path,
+                                &settings,
+                                *prover_backend,
+                                player_id.0 as u64,
+                                result.trace_hash,
+                                &result.public_inputs,
+                                &proof_bytes,
+                                current_time,
+                            ) {
+                                warn!("Failed to append proof to on-disk log {}: {}", log_config.path.display(), e);
+                            }
+                        }
+
+                        hud_log.push(format!(
+                            "✅ Player {} proof #{} verified ({} bytes, {:.0}ms)",
+                            player_id.0, proof_gen.stats.total_proofs_generated, proof_size, result.generation_time_ms
+                        ));
+
+                        if let Err(e) = crate::metrics_export::record_proof_metric(
+                            &metrics_config,
+                            &mut metrics_state,
+                            settings.metrics_format,
+                            crate::metrics_export::ProofMetricRecord {
+                                player_id: player_id.0,
+                                generation_time_ms: result.generation_time_ms,
+                                verification_time_ms: result.verification_time_ms,
+                                proof_size,
+                                success: true,
+                                cheat_detected: false,
+                                failure_message: None,
+                            },
+                        ) {
+                            warn!("Failed to export proof metrics: {e}");
+                        }
+
+                        if !proof_bytes.is_empty() {
+                            aggregator.push(crate::proof_aggregation::BufferedProof {
+                                player_id: player_id.0,
+                                trace_hash: result.trace_hash,
+                                public_inputs: result.public_inputs.clone(),
+                                artifact: crate::prover::ProvedArtifact { bytes: proof_bytes },
+                            });
+
+                            if aggregator.is_ready() {
+                                if let Some((aggregate, verify_time_ms)) = aggregator.flush(*prover_backend) {
+                                    proof_gen.stats.record_aggregate(aggregate.constituent_count, aggregate.size(), verify_time_ms);
+                                    info!(
+                                        "📦 Folded {} proofs into one {}-byte aggregate (all_verified={}) in {:.2}ms",
+                                        aggregate.constituent_count, aggregate.size(), aggregate.all_verified, verify_time_ms
+                                    );
+                                    hud_log.push(format!(
+                                        "📦 Aggregated {} proofs (all_verified={})",
+                                        aggregate.constituent_count, aggregate.all_verified
+                                    ));
+                                }
+                            }
+                        }
                     }
                     Err(e) => {
-                        if e.starts_with("CHEAT_DETECTED:") {
+                        let cheat_detected = e.starts_with("CHEAT_DETECTED:");
+                        if cheat_detected {
                             error!("🚨 CHEAT DETECTED: {}", e);
                             // Trigger cheat detection UI by inserting resource
                             commands.insert_resource(crate::CheatDetected {
-                                message: "CHEATER DETECTED!\nInvalid proof verification failed!\nPress ESC to continue".to_string(),
+                                player: Some(*player_id),
+                                message: format!("CHEATER DETECTED!\nPlayer {} failed proof verification!\nPress ESC to continue", player_id.0),
                                 is_active: true,
                             });
                         } else {
                             error!("❌ Async proof generation failed: {}", e);
                         }
                         proof_gen.stats.failed_verifications += 1;
+                        hud_log.push(format!("❌ Player {} proof failed: {}", player_id.0, e));
+
+                        if let Err(export_err) = crate::metrics_export::record_proof_metric(
+                            &metrics_config,
+                            &mut metrics_state,
+                            settings.metrics_format,
+                            crate::metrics_export::ProofMetricRecord {
+                                player_id: player_id.0,
+                                generation_time_ms: result.generation_time_ms,
+                                verification_time_ms: result.verification_time_ms,
+                                proof_size: 0,
+                                success: false,
+                                cheat_detected,
+                                failure_message: Some(e.clone()),
+                            },
+                        ) {
+                            warn!("Failed to export proof metrics: {export_err}");
+                        }
                     }
                 }
             } else {
@@ -213,68 +528,104 @@ pub fn proof_generation_system(
     }
 }
 
-async fn generate_proof_async(trace: &MovementTrace) -> (Result<(Vec<u8>, usize), String>, f64) {
-    // Create STARK config inside the async function (each task gets its own)
-    let (config, air) = create_stark_config();
-    
+/// Folds whatever `ProofAggregator` still has buffered into one final
+/// `AggregateArtifact` when the app is closing, instead of leaving a partial batch
+/// unfolded - the flush-on-session-end path the aggregator needs alongside its
+/// normal batch-size-triggered flush in `proof_generation_system`.
+pub fn flush_aggregator_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    prover_backend: Res<ProverBackend>,
+    mut aggregator: ResMut<crate::proof_aggregation::ProofAggregator>,
+    mut query: Query<&mut ProofGenerator, With<Player>>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    if let Some((aggregate, verify_time_ms)) = aggregator.flush(*prover_backend) {
+        info!(
+            "📦 Session-end flush: folded {} proofs into one {}-byte aggregate (all_verified={}) in {:.2}ms",
+            aggregate.constituent_count, aggregate.size(), aggregate.all_verified, verify_time_ms
+        );
+        if let Some(mut proof_gen) = query.iter_mut().next() {
+            proof_gen.stats.record_aggregate(aggregate.constituent_count, aggregate.size(), verify_time_ms);
+        }
+    }
+}
+
+async fn generate_proof_async(
+    trace: &MovementTrace,
+    sanctioned: bool,
+    backend: ProverBackend,
+) -> (Result<(Vec<u8>, usize), String>, f64, Vec<String>) {
+    let settings = ProofSystemSettings::default();
+
     // Find appropriate trace height (next power of 2)
     let target_height = next_power_of_2(trace.steps.len().max(8));
-    
+
+    // MovementAir proves a full simulation frame's worth of agent slots at once;
+    // this caller only tracks a single player, so the remaining slots are padded
+    // as stationary NPCs by `generate_movement_trace_matrix` itself.
+    let traces = std::slice::from_ref(trace);
+
     // Generate trace matrix
-    let trace_matrix = generate_movement_trace_matrix::<Val>(trace, target_height);
-    
-
-    // Generate proof (this is the heavy computation that runs on background thread)
-    println!("🔥 ABOUT TO CALL PROVE() - trace matrix has {} rows", trace_matrix.height());
-    
-    // Catch panics during proving (constraint violations cause panics)
-    let proof_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        prove(&config, &air, trace_matrix, &vec![])
-    }));
-    
-    let proof = match proof_result {
-        Ok(proof) => {
+    let trace_matrix = generate_movement_trace_matrix::<Val>(traces, target_height, settings.game_bounds);
+
+    // Cheap, readable pre-check before paying for whichever backend's prove() is
+    // configured. A genuinely invalid trace can never produce a valid proof under
+    // any backend - the math itself can't be "exempted" - so for a sanctioned
+    // player we skip proving entirely rather than spend time proving something we
+    // already know will fail.
+    let mut diagnostics = Vec::new();
+    if let Err(violations) = check_movement_constraints(&trace_matrix) {
+        for violation in &violations {
+            if sanctioned {
+                info!("🛡️ SANCTIONED CHEAT (row {}, agent {}): {}", violation.row, violation.agent, violation.message);
+            } else {
+                warn!("🔍 CONSTRAINT VIOLATION (row {}, agent {}): {}", violation.row, violation.agent, violation.message);
+            }
+            diagnostics.push(format!("Row {}: {}", violation.row, violation.message));
+        }
+        if sanctioned {
+            return (Ok((Vec::new(), 0)), 0.0, diagnostics);
+        }
+    }
+
+    // Dispatch the actual prove/verify pass through the selected backend, rather
+    // than calling Plonky3 directly - lets a CI run (or a player trading latency
+    // for soundness) swap in `ProverBackend::MockNativeReplay` with no code change
+    // here.
+    let prover = backend.build();
+
+    println!("🔥 ABOUT TO CALL prove() - trace has {} steps", trace.steps.len());
+    let artifact = match prover.prove(traces) {
+        Ok(artifact) => {
             println!("✅ PROVE() SUCCEEDED - proof generated without constraint failures");
-            proof
+            artifact
         }
-        Err(_panic_info) => {
-            println!("❌ PROVE() FAILED - constraint violation detected during proving");
-            return (Err("CHEAT_DETECTED: Constraint violation during proof generation".to_string()), 0.0);
+        Err(e) => {
+            println!("❌ PROVE() FAILED - {e}");
+            return (Err(format!("CHEAT_DETECTED: {e}")), 0.0, diagnostics);
         }
     };
-    
-    // Serialize proof to get size
-    let proof_bytes = match bincode::serialize(&proof) {
-        Ok(bytes) => bytes,
-        Err(e) => return (Err(format!("Proof serialization failed: {:?}", e)), 0.0),
-    };
-    
-    let proof_size = proof_bytes.len();
-    
+    let proof_size = artifact.size();
+
     // VERIFY THE PROOF - this is critical for anti-cheat!
-    println!("🔍 VERIFYING PROOF - checking mathematical validity...");
+    println!("🔍 VERIFYING PROOF - checking validity...");
     let verification_start = Instant::now();
-    let verification_result = match bincode::deserialize::<_>(&proof_bytes) {
-        Ok(deserialized_proof) => {
-            match verify(&config, &air, &deserialized_proof, &vec![]) {
-                Ok(_) => {
-                    println!("✅ PROOF VERIFICATION PASSED - proof is mathematically valid");
-                    Ok((proof_bytes, proof_size))
-                }
-                Err(e) => {
-                    println!("❌ PROOF VERIFICATION FAILED - proof is invalid: {:?}", e);
-                    Err(format!("CHEAT_DETECTED: Invalid proof: {:?}", e))
-                }
-            }
+    let verification_result = match prover.verify(&artifact) {
+        Ok(()) => {
+            println!("✅ PROOF VERIFICATION PASSED - proof is valid");
+            Ok((artifact.bytes, proof_size))
         }
         Err(e) => {
-            println!("❌ PROOF DESERIALIZATION FAILED: {:?}", e);
-            Err(format!("CHEAT_DETECTED: Corrupted proof: {:?}", e))
+            println!("❌ PROOF VERIFICATION FAILED - {e}");
+            Err(format!("CHEAT_DETECTED: {e}"))
         }
     };
     let verification_time = verification_start.elapsed().as_millis() as f64;
-    
-    (verification_result, verification_time)
+
+    (verification_result, verification_time, diagnostics)
 }
 
 
@@ -289,6 +640,8 @@ pub fn stats_logging_system(
         for proof_gen in &query {
             let stats = &proof_gen.stats;
             if stats.total_proofs_generated > 0 || !proof_gen.active_tasks.is_empty() {
+                let gen_summary = stats.generation_summary();
+                let verify_summary = stats.verification_summary();
                 info!(
                     "📊 Proof Stats - Active: {}, Generated: {}, Avg Gen: {:.1}ms, Avg Verify: {:.1}ms, Success: {:.1}%",
                     proof_gen.active_tasks.len(),
@@ -299,7 +652,92 @@ pub fn stats_logging_system(
                         stats.successful_verifications as f64 / (stats.successful_verifications + stats.failed_verifications) as f64 * 100.0
                     } else { 0.0 }
                 );
+                info!(
+                    "📊 Proof Gen Tail - p50: {:.1}ms, p95: {:.1}ms, p99: {:.1}ms, stddev: {:.1}ms, Winsorized mean: {:.1}ms",
+                    gen_summary.median, gen_summary.p95, gen_summary.p99, gen_summary.stddev, gen_summary.winsorized_mean
+                );
+                info!(
+                    "📊 Proof Verify Tail - p50: {:.1}ms, p95: {:.1}ms, p99: {:.1}ms, stddev: {:.1}ms, Winsorized mean: {:.1}ms",
+                    verify_summary.median, verify_summary.p95, verify_summary.p99, verify_summary.stddev, verify_summary.winsorized_mean
+                );
             }
         }
     }
+}
+
+/// Holds the in-flight tasks for `npc_proof_generation_system`. Deliberately not a
+/// `ProofGenerator`-shaped resource (no per-entity stats, no scheduler lanes) - NPCs
+/// don't have a player to attribute a cheat finding to, so this pipeline only needs
+/// to know whether a proof round is still running.
+#[derive(Resource, Default)]
+pub struct NpcProofState {
+    active_tasks: Vec<Task<NpcProofResult>>,
+}
+
+struct NpcProofResult {
+    outcome: Result<(), String>,
+    generation_time_ms: f64,
+}
+
+/// Separate from `proof_generation_system`'s per-player pipeline: the NPCs have no
+/// `ProofGenerator`/stats of their own to attribute a result to, and bundling a real
+/// player's trace into the same proof as an NPC's would make the NPC's own
+/// `npc_separation_system` velocity (which only reacts to other NPCs) disagree with
+/// the neighbor set the proof actually checks against. Proving every NPC's trace
+/// together, and only together, keeps them in sync and gives `MovementAir`'s
+/// separation constraint its first genuinely non-placeholder exercise.
+pub fn npc_proof_generation_system(
+    prover_backend: Res<ProverBackend>,
+    mut npc_state: ResMut<NpcProofState>,
+    mut hud_log: ResMut<HudLog>,
+    mut query: Query<&mut MovementTraceCollector, With<Npc>>,
+) {
+    let npc_count = query.iter().count();
+    if npc_count >= 2 && query.iter().all(|collector| collector.has_trace_ready()) {
+        let traces: Vec<MovementTrace> = query
+            .iter_mut()
+            .filter_map(|mut collector| collector.get_next_trace_for_proving())
+            .filter(|trace| trace.steps.len() > 1)
+            .collect();
+
+        // The AIR's separation constraint needs every populated slot's position at
+        // the same row index. Both collectors tick on the same cadence, so their
+        // completed traces are virtually always the same length already - on the
+        // rare tick they aren't, drop this round rather than feed
+        // `generate_movement_trace_matrix` a length mismatch it would silently pad
+        // around (and desync `expected_initial_state` chaining for the next round).
+        let lengths_match = traces.windows(2).all(|pair| pair[0].steps.len() == pair[1].steps.len());
+
+        if traces.len() >= 2 && lengths_match {
+            let task_pool = AsyncComputeTaskPool::get();
+            let backend = *prover_backend;
+            #[allow(unused_must_use)]
+            let task = task_pool.spawn(async move {
+                let generation_start = Instant::now();
+                let prover = backend.build();
+                let outcome = prover
+                    .prove(&traces)
+                    .and_then(|artifact| prover.verify(&artifact))
+                    .map_err(|e| e.to_string());
+                NpcProofResult {
+                    outcome,
+                    generation_time_ms: generation_start.elapsed().as_millis() as f64,
+                }
+            });
+            npc_state.active_tasks.push(task);
+        }
+    }
+
+    let mut i = 0;
+    while i < npc_state.active_tasks.len() {
+        if let Some(result) = future::block_on(future::poll_once(&mut npc_state.active_tasks[i])) {
+            npc_state.active_tasks.remove(i);
+            match result.outcome {
+                Ok(()) => hud_log.push(format!("🐦 NPC flock proof verified ({:.0}ms)", result.generation_time_ms)),
+                Err(e) => hud_log.push(format!("⚠️ NPC flock proof failed: {e}")),
+            }
+        } else {
+            i += 1;
+        }
+    }
 }
\ No newline at end of file
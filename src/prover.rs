@@ -0,0 +1,209 @@
+// Pluggable proving backends behind one trait, so `proof_generation_system` doesn't
+// have to hardcode Plonky3/BabyBear. A proving service needs exactly this shape: an
+// interchangeable native/zk backend behind one task interface, so a CI run (or a
+// player who wants lower latency over cryptographic soundness) can swap the real
+// STARK for a cheap native replay without touching the caller.
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::{TwoAdicFriPcs, create_test_fri_params};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{StarkConfig, prove, verify};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use bevy::prelude::Resource;
+
+use crate::check_constraints::check_movement_constraints;
+use crate::movement_air::{MovementAir, generate_movement_trace_matrix, next_power_of_2, trace_public_values};
+use crate::movement_trace::MovementTrace;
+use crate::proof_system::ProofSystemSettings;
+
+// Type aliases for the Plonky3 STARK configuration - moved here from proof_system.rs
+// so this concrete config is an implementation detail of one backend, not baked into
+// the caller.
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs = MerkleTreeMmcs<<Val as p3_field::Field>::Packing, <Val as p3_field::Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+/// Builds the STARK config and AIR instance for `settings` - parameterized (rather
+/// than always defaulting `ProofSystemSettings`) so `proof_log::verify_log` can
+/// reconstruct the exact constraint system a proof was generated under from a log
+/// file's recorded header, without needing the live game's settings at all.
+pub(crate) fn build_stark_config(settings: &ProofSystemSettings) -> (MyConfig, MovementAir) {
+    let mut rng = SmallRng::seed_from_u64(42); // Fixed seed for reproducibility
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft::default();
+
+    let fri_params = create_test_fri_params(challenge_mmcs, 2);
+    let pcs = Pcs::new(dft, val_mmcs, fri_params);
+    let challenger = Challenger::new(perm);
+
+    let config = MyConfig::new(pcs, challenger);
+    let air = MovementAir::new(settings.movement_speed, settings.game_bounds, settings.delta_time);
+
+    (config, air)
+}
+
+/// Deserializes and re-verifies a `Plonky3Prover`-produced artifact under
+/// `settings` instead of always assuming `ProofSystemSettings::default()` - the
+/// piece `proof_log::verify_log` needs to check a record against its log header's
+/// recorded settings rather than whatever the verifying process happens to be
+/// running with.
+pub(crate) fn verify_artifact_with_settings(settings: &ProofSystemSettings, bytes: &[u8]) -> Result<(), ProverError> {
+    let (config, air) = build_stark_config(settings);
+
+    let (proof, public_values): (_, Vec<Val>) = bincode::deserialize(bytes)
+        .map_err(|e| ProverError::Verification(format!("corrupted proof: {e:?}")))?;
+
+    verify(&config, &air, &proof, &public_values)
+        .map_err(|e| ProverError::Verification(format!("invalid proof: {e:?}")))
+}
+
+/// Opaque output of a `MovementProver::prove` call - a serialized STARK proof for
+/// `Plonky3Prover`, or just a small fingerprint for `MockProver`. Only the backend
+/// that produced an artifact knows how to interpret its bytes in `verify`.
+#[derive(Debug, Clone)]
+pub struct ProvedArtifact {
+    pub bytes: Vec<u8>,
+}
+
+impl ProvedArtifact {
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ProverError {
+    Generation(String),
+    Verification(String),
+}
+
+impl std::fmt::Display for ProverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProverError::Generation(msg) => write!(f, "proof generation failed: {msg}"),
+            ProverError::Verification(msg) => write!(f, "proof verification failed: {msg}"),
+        }
+    }
+}
+
+/// One interchangeable proving backend. `Send + Sync` so an instance can be moved
+/// into an `AsyncComputeTaskPool` proof-generation task.
+pub trait MovementProver: Send + Sync {
+    fn prove(&self, traces: &[MovementTrace]) -> Result<ProvedArtifact, ProverError>;
+    fn verify(&self, artifact: &ProvedArtifact) -> Result<(), ProverError>;
+}
+
+/// Which `MovementProver` implementation `proof_generation_system` dispatches
+/// through, chosen once at startup. `Plonky3Stark` is the real cryptographic
+/// anti-cheat; `MockNativeReplay` trades that soundness guarantee for near-zero
+/// latency, e.g. for CI runs that only care whether the game logic itself is correct.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProverBackend {
+    #[default]
+    Plonky3Stark,
+    MockNativeReplay,
+}
+
+impl ProverBackend {
+    pub fn build(&self) -> Box<dyn MovementProver> {
+        match self {
+            ProverBackend::Plonky3Stark => Box::new(Plonky3Prover),
+            ProverBackend::MockNativeReplay => Box::new(MockProver),
+        }
+    }
+}
+
+/// The existing Plonky3 STARK implementation, now behind `MovementProver` instead of
+/// being called directly from `proof_system.rs`.
+pub struct Plonky3Prover;
+
+impl MovementProver for Plonky3Prover {
+    fn prove(&self, traces: &[MovementTrace]) -> Result<ProvedArtifact, ProverError> {
+        let settings = ProofSystemSettings::default();
+        let (config, air) = build_stark_config(&settings);
+
+        let target_height = next_power_of_2(traces.iter().map(|t| t.steps.len()).max().unwrap_or(0).max(8));
+        // MovementAir proves a full simulation frame's worth of agent slots at once;
+        // any slots `traces` doesn't fill (e.g. a single player with no NPCs nearby)
+        // are padded as stationary NPCs by `generate_movement_trace_matrix` itself.
+        let trace_matrix = generate_movement_trace_matrix::<Val>(traces, target_height, settings.game_bounds);
+
+        // Public values bind this proof's first/last row position (per agent slot),
+        // so the next trace's proof can be checked to continue from exactly where
+        // this one left off.
+        let public_values = trace_public_values::<Val>(traces);
+
+        // Catch panics during proving (constraint violations cause panics).
+        let proof_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            prove(&config, &air, trace_matrix, &public_values)
+        }));
+
+        let proof = proof_result
+            .map_err(|_| ProverError::Generation("constraint violation during proof generation".to_string()))?;
+
+        let bytes = bincode::serialize(&(proof, public_values))
+            .map_err(|e| ProverError::Generation(format!("proof serialization failed: {e:?}")))?;
+
+        Ok(ProvedArtifact { bytes })
+    }
+
+    fn verify(&self, artifact: &ProvedArtifact) -> Result<(), ProverError> {
+        verify_artifact_with_settings(&ProofSystemSettings::default(), &artifact.bytes)
+    }
+}
+
+/// A cheap native-replay backend: "proving" a trace just means re-running
+/// `check_movement_constraints`, the off-chain mirror of the AIR's boolean-input,
+/// velocity-from-inputs and position-continuity constraints, with no actual
+/// cryptography. The AIR's range-check, bounds, separation and public-value
+/// constraints are not checked here. Not trustless the way `Plonky3Prover` is - a malicious client could
+/// report success without really checking - so this exists for CI/local runs that
+/// want the game logic validated quickly, not for a real anti-cheat deployment.
+pub struct MockProver;
+
+impl MovementProver for MockProver {
+    fn prove(&self, traces: &[MovementTrace]) -> Result<ProvedArtifact, ProverError> {
+        let settings = ProofSystemSettings::default();
+        let target_height = next_power_of_2(traces.iter().map(|t| t.steps.len()).max().unwrap_or(0).max(8));
+        let trace_matrix = generate_movement_trace_matrix::<Val>(traces, target_height, settings.game_bounds);
+
+        if let Err(violations) = check_movement_constraints(&trace_matrix) {
+            let messages = violations
+                .iter()
+                .map(|v| format!("Row {}: {}", v.row, v.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(ProverError::Generation(messages));
+        }
+
+        // The "proof" is just a fingerprint of how long the longest replayed trace
+        // was - there's nothing cryptographic to serialize, since the replay above is
+        // the entire check.
+        let longest = traces.iter().map(|t| t.steps.len()).max().unwrap_or(0);
+        Ok(ProvedArtifact { bytes: (longest as u32).to_le_bytes().to_vec() })
+    }
+
+    fn verify(&self, _artifact: &ProvedArtifact) -> Result<(), ProverError> {
+        // The native replay in `prove` already is the verification - there's no
+        // separate party to convince, so accepting any artifact it produced is
+        // correct for this backend.
+        Ok(())
+    }
+}
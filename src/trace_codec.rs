@@ -0,0 +1,279 @@
+// Compact binary codec for shipping a `MovementTrace` to a standalone prover
+// service, or persisting it for later replay, without paying for a full JSON/Vec2
+// encoding of every step. Positions rarely move far frame-to-frame, so each step
+// delta-encodes its position as an `i16` offset from the previous step, escaping to
+// a full `i32` absolute value on the rare frame where that doesn't fit.
+use bevy::prelude::Vec2;
+use nom::bytes::complete::tag;
+use nom::combinator::verify;
+use nom::multi::many0;
+use nom::number::complete::{le_f64, le_i16, le_i32, le_u16, le_u32, le_u8};
+use nom::IResult;
+
+use crate::movement_air::DELTA_TIME_SCALE;
+use crate::movement_trace::{InputFlags, MovementStep, MovementTrace};
+
+const MAGIC: [u8; 4] = *b"MVTR";
+const FORMAT_VERSION: u8 = 2;
+
+const INPUT_LEFT_BIT: u8 = 1 << 0;
+const INPUT_RIGHT_BIT: u8 = 1 << 1;
+const INPUT_UP_BIT: u8 = 1 << 2;
+const INPUT_DOWN_BIT: u8 = 1 << 3;
+const X_ESCAPED_BIT: u8 = 1 << 4;
+const Y_ESCAPED_BIT: u8 = 1 << 5;
+
+/// Serializes `trace` into the wire format `from_bytes` reads back. Does not encode
+/// `expected_initial_state` - that's proof-chaining bookkeeping local to this
+/// session, not part of a trace's on-the-wire identity.
+pub fn to_bytes(trace: &MovementTrace) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&trace.start_time.to_le_bytes());
+    out.extend_from_slice(&trace.duration.to_le_bytes());
+    out.extend_from_slice(&(trace.steps.len() as u32).to_le_bytes());
+
+    let mut prev_position: Option<(i32, i32)> = None;
+    for step in &trace.steps {
+        let position = (step.position.x.round() as i32, step.position.y.round() as i32);
+        let x_escaped = !delta_fits_i16(prev_position.map(|p| p.0), position.0);
+        let y_escaped = !delta_fits_i16(prev_position.map(|p| p.1), position.1);
+
+        let mut flags = 0u8;
+        if step.inputs.left {
+            flags |= INPUT_LEFT_BIT;
+        }
+        if step.inputs.right {
+            flags |= INPUT_RIGHT_BIT;
+        }
+        if step.inputs.up {
+            flags |= INPUT_UP_BIT;
+        }
+        if step.inputs.down {
+            flags |= INPUT_DOWN_BIT;
+        }
+        if x_escaped {
+            flags |= X_ESCAPED_BIT;
+        }
+        if y_escaped {
+            flags |= Y_ESCAPED_BIT;
+        }
+        out.push(flags);
+
+        if x_escaped {
+            out.extend_from_slice(&position.0.to_le_bytes());
+        } else {
+            let dx = (position.0 - prev_position.unwrap().0) as i16;
+            out.extend_from_slice(&dx.to_le_bytes());
+        }
+        if y_escaped {
+            out.extend_from_slice(&position.1.to_le_bytes());
+        } else {
+            let dy = (position.1 - prev_position.unwrap().1) as i16;
+            out.extend_from_slice(&dy.to_le_bytes());
+        }
+        prev_position = Some(position);
+
+        out.extend_from_slice(&(step.velocity.x.round() as i16).to_le_bytes());
+        out.extend_from_slice(&(step.velocity.y.round() as i16).to_le_bytes());
+
+        let delta_time_scaled = (step.delta_time * DELTA_TIME_SCALE as f32).round() as u16;
+        out.extend_from_slice(&delta_time_scaled.to_le_bytes());
+
+        out.extend_from_slice(&step.frame.to_le_bytes());
+    }
+
+    out
+}
+
+/// Whether `current` can be written as an `i16` delta from `prev` (always false for
+/// the first step, which has no previous position to delta against).
+fn delta_fits_i16(prev: Option<i32>, current: i32) -> bool {
+    match prev {
+        None => false,
+        Some(prev) => {
+            let delta = current - prev;
+            (i16::MIN as i32..=i16::MAX as i32).contains(&delta)
+        }
+    }
+}
+
+/// Parses a trace previously serialized with `to_bytes`.
+pub fn from_bytes(input: &[u8]) -> Result<MovementTrace, String> {
+    parse_trace(input)
+        .map(|(_, trace)| trace)
+        .map_err(|e| format!("Failed to parse movement trace: {e}"))
+}
+
+struct RawStep {
+    flags: u8,
+    // The i16 delta (sign-extended) or, when its escape bit is set, the full
+    // absolute position - `finalize_steps` below tells the two apart.
+    pos_x_field: i32,
+    pos_y_field: i32,
+    velocity_x: i16,
+    velocity_y: i16,
+    delta_time_scaled: u16,
+    frame: i32,
+}
+
+fn parse_header(input: &[u8]) -> IResult<&[u8], (f64, f64, u32)> {
+    let (input, _) = tag(&MAGIC[..])(input)?;
+    let (input, _version) = verify(le_u8, |v: &u8| *v == FORMAT_VERSION)(input)?;
+    let (input, start_time) = le_f64(input)?;
+    let (input, duration) = le_f64(input)?;
+    let (input, step_count) = le_u32(input)?;
+    Ok((input, (start_time, duration, step_count)))
+}
+
+fn parse_raw_step(input: &[u8]) -> IResult<&[u8], RawStep> {
+    let (input, flags) = le_u8(input)?;
+
+    let (input, pos_x_field) = if flags & X_ESCAPED_BIT != 0 {
+        le_i32(input)?
+    } else {
+        let (input, dx) = le_i16(input)?;
+        (input, dx as i32)
+    };
+    let (input, pos_y_field) = if flags & Y_ESCAPED_BIT != 0 {
+        le_i32(input)?
+    } else {
+        let (input, dy) = le_i16(input)?;
+        (input, dy as i32)
+    };
+
+    let (input, velocity_x) = le_i16(input)?;
+    let (input, velocity_y) = le_i16(input)?;
+    let (input, delta_time_scaled) = le_u16(input)?;
+    let (input, frame) = le_i32(input)?;
+
+    Ok((
+        input,
+        RawStep {
+            flags,
+            pos_x_field,
+            pos_y_field,
+            velocity_x,
+            velocity_y,
+            delta_time_scaled,
+            frame,
+        },
+    ))
+}
+
+fn parse_trace(input: &[u8]) -> IResult<&[u8], MovementTrace> {
+    let (input, (start_time, duration, step_count)) = parse_header(input)?;
+    let (input, raw_steps) = many0(parse_raw_step)(input)?;
+
+    // Like `expected_initial_state`, `is_player` isn't part of a trace's on-the-wire
+    // identity - this codec only ever ships a player's trace to a standalone prover
+    // service, so `true` is the only value a decoded trace can mean here.
+    let mut trace = MovementTrace::new(start_time, None, true);
+    trace.duration = duration;
+    trace.steps = finalize_steps(start_time, &raw_steps[..step_count as usize]);
+
+    Ok((input, trace))
+}
+
+/// Rebuilds absolute positions (and step timestamps) from the delta-encoded raw
+/// steps, in the order `to_bytes` wrote them.
+fn finalize_steps(start_time: f64, raw_steps: &[RawStep]) -> Vec<MovementStep> {
+    let mut steps = Vec::with_capacity(raw_steps.len());
+    let mut prev_position: Option<(i32, i32)> = None;
+    let mut timestamp = start_time;
+
+    for raw in raw_steps {
+        let prev = prev_position.unwrap_or((0, 0));
+        let x = if raw.flags & X_ESCAPED_BIT != 0 {
+            raw.pos_x_field
+        } else {
+            prev.0 + raw.pos_x_field
+        };
+        let y = if raw.flags & Y_ESCAPED_BIT != 0 {
+            raw.pos_y_field
+        } else {
+            prev.1 + raw.pos_y_field
+        };
+        prev_position = Some((x, y));
+
+        let delta_time = raw.delta_time_scaled as f32 / DELTA_TIME_SCALE as f32;
+        timestamp += raw.delta_time_scaled as f64 / DELTA_TIME_SCALE as f64;
+
+        steps.push(MovementStep {
+            position: Vec2::new(x as f32, y as f32),
+            velocity: Vec2::new(raw.velocity_x as f32, raw.velocity_y as f32),
+            inputs: InputFlags {
+                left: raw.flags & INPUT_LEFT_BIT != 0,
+                right: raw.flags & INPUT_RIGHT_BIT != 0,
+                up: raw.flags & INPUT_UP_BIT != 0,
+                down: raw.flags & INPUT_DOWN_BIT != 0,
+            },
+            timestamp,
+            delta_time,
+            frame: raw.frame,
+        });
+    }
+
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: f32, y: f32, vx: f32, vy: f32, timestamp: f64, delta_time: f32, frame: i32, inputs: InputFlags) -> MovementStep {
+        MovementStep {
+            position: Vec2::new(x, y),
+            velocity: Vec2::new(vx, vy),
+            inputs,
+            timestamp,
+            delta_time,
+            frame,
+        }
+    }
+
+    #[test]
+    fn round_trips_typical_trace() {
+        let mut trace = MovementTrace::new(10.0, None, true);
+        trace.add_step(step(0.0, 0.0, 0.0, 0.0, 10.0, 0.0, 0, InputFlags::default()));
+        trace.add_step(step(3.0, 0.0, 200.0, 0.0, 10.016, 0.016, 1, InputFlags { right: true, ..Default::default() }));
+        trace.add_step(step(6.0, -3.0, 200.0, -200.0, 10.032, 0.016, 2, InputFlags { right: true, down: true, ..Default::default() }));
+
+        let bytes = to_bytes(&trace);
+        let decoded = from_bytes(&bytes).expect("valid trace bytes should parse");
+
+        assert_eq!(decoded.start_time, trace.start_time);
+        assert_eq!(decoded.duration, trace.duration);
+        assert_eq!(decoded.steps.len(), trace.steps.len());
+        for (original, decoded) in trace.steps.iter().zip(decoded.steps.iter()) {
+            assert_eq!(decoded.position, original.position);
+            assert_eq!(decoded.velocity, original.velocity);
+            assert_eq!(decoded.inputs.left, original.inputs.left);
+            assert_eq!(decoded.inputs.right, original.inputs.right);
+            assert_eq!(decoded.inputs.up, original.inputs.up);
+            assert_eq!(decoded.inputs.down, original.inputs.down);
+            assert_eq!(decoded.delta_time, original.delta_time);
+            assert_eq!(decoded.frame, original.frame);
+            assert!((decoded.timestamp - original.timestamp).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn round_trips_large_single_frame_jump() {
+        // A delta this large can't fit in an i16, forcing the escape path.
+        let mut trace = MovementTrace::new(0.0, None, true);
+        trace.add_step(step(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0, InputFlags::default()));
+        trace.add_step(step(50_000.0, -40_000.0, 0.0, 0.0, 0.016, 0.016, 1, InputFlags::default()));
+
+        let bytes = to_bytes(&trace);
+        let decoded = from_bytes(&bytes).expect("valid trace bytes should parse");
+
+        assert_eq!(decoded.steps[1].position, trace.steps[1].position);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        assert!(from_bytes(b"NOPE").is_err());
+    }
+}